@@ -0,0 +1,193 @@
+#![cfg_attr(not(any(test, feature = "export-abi")), no_main)]
+#![cfg_attr(not(any(test, feature = "export-abi")), no_std)]
+
+#[macro_use]
+extern crate alloc;
+
+use alloc::{string::String, vec::Vec};
+use alloy_sol_types::sol;
+use stylus_sdk::{
+    alloy_primitives::{Address, U256},
+    prelude::*,
+};
+
+sol_storage! {
+    #[entrypoint]
+    pub struct GoldToken {
+        address minter;
+        uint256 total_supply;
+        mapping(address => uint256) balances;
+        mapping(address => mapping(address => uint256)) allowances;
+    }
+}
+
+sol! {
+    event Transfer(address indexed from, address indexed to, uint256 value);
+    event Approval(address indexed owner, address indexed spender, uint256 value);
+
+    #[derive(Debug)]
+    error NotMinter(address caller);
+    #[derive(Debug)]
+    error InsufficientBalance(address from, uint256 balance, uint256 requested);
+    #[derive(Debug)]
+    error InsufficientAllowance(address owner, address spender, uint256 allowance, uint256 requested);
+}
+
+#[derive(SolidityError, Debug)]
+pub enum GoldError {
+    NotMinter(NotMinter),
+    InsufficientBalance(InsufficientBalance),
+    InsufficientAllowance(InsufficientAllowance),
+}
+
+#[public]
+impl GoldToken {
+    /// `minter` is the sword contract; it is the only account that can create gold.
+    #[constructor]
+    pub fn constructor(&mut self, minter: Address) {
+        self.minter.set(minter);
+    }
+
+    pub fn name(&self) -> String {
+        String::from("Gold")
+    }
+
+    pub fn symbol(&self) -> String {
+        String::from("GOLD")
+    }
+
+    pub fn decimals(&self) -> u8 {
+        18
+    }
+
+    pub fn minter(&self) -> Address {
+        self.minter.get()
+    }
+
+    pub fn total_supply(&self) -> U256 {
+        self.total_supply.get()
+    }
+
+    pub fn balance_of(&self, owner: Address) -> U256 {
+        self.balances.get(owner)
+    }
+
+    pub fn allowance(&self, owner: Address, spender: Address) -> U256 {
+        self.allowances.get(owner).get(spender)
+    }
+
+    pub fn transfer(&mut self, to: Address, value: U256) -> Result<bool, GoldError> {
+        let from = self.vm().msg_sender();
+        self.move_tokens(from, to, value)?;
+        Ok(true)
+    }
+
+    pub fn approve(&mut self, spender: Address, value: U256) -> bool {
+        let owner = self.vm().msg_sender();
+        self.allowances.setter(owner).insert(spender, value);
+        log(self.vm(), Approval { owner, spender, value });
+        true
+    }
+
+    pub fn transfer_from(&mut self, from: Address, to: Address, value: U256) -> Result<bool, GoldError> {
+        let spender = self.vm().msg_sender();
+        let allowance = self.allowance(from, spender);
+        if allowance < value {
+            return Err(GoldError::InsufficientAllowance(InsufficientAllowance {
+                owner: from,
+                spender,
+                allowance,
+                requested: value,
+            }));
+        }
+        self.allowances.setter(from).insert(spender, allowance - value);
+        self.move_tokens(from, to, value)?;
+        Ok(true)
+    }
+
+    pub fn mint(&mut self, to: Address, amount: U256) -> Result<(), GoldError> {
+        let caller = self.vm().msg_sender();
+        if caller != self.minter.get() {
+            return Err(GoldError::NotMinter(NotMinter { caller }));
+        }
+        let balance = self.balances.get(to);
+        self.balances.insert(to, balance + amount);
+        self.total_supply.set(self.total_supply.get() + amount);
+        log(self.vm(), Transfer { from: Address::ZERO, to, value: amount });
+        Ok(())
+    }
+}
+
+impl GoldToken {
+    fn move_tokens(&mut self, from: Address, to: Address, value: U256) -> Result<(), GoldError> {
+        let balance = self.balances.get(from);
+        if balance < value {
+            return Err(GoldError::InsufficientBalance(InsufficientBalance {
+                from,
+                balance,
+                requested: value,
+            }));
+        }
+        self.balances.insert(from, balance - value);
+        let to_balance = self.balances.get(to);
+        self.balances.insert(to, to_balance + value);
+        log(self.vm(), Transfer { from, to, value });
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use stylus_sdk::testing::*;
+
+    const SWORDS: Address = Address::repeat_byte(0x5a);
+    const PLAYER: Address = Address::repeat_byte(0x01);
+    const FRIEND: Address = Address::repeat_byte(0x02);
+
+    fn deploy(vm: &TestVM) -> GoldToken {
+        let mut token = GoldToken::from(vm);
+        token.constructor(SWORDS);
+        token
+    }
+
+    #[test]
+    fn only_the_sword_contract_can_mint() {
+        let vm = TestVM::default();
+        let mut token = deploy(&vm);
+
+        vm.set_sender(PLAYER);
+        assert!(matches!(token.mint(PLAYER, U256::from(5)), Err(GoldError::NotMinter(_))));
+
+        vm.set_sender(SWORDS);
+        token.mint(PLAYER, U256::from(5)).unwrap();
+        assert_eq!(token.balance_of(PLAYER), U256::from(5));
+        assert_eq!(token.total_supply(), U256::from(5));
+    }
+
+    #[test]
+    fn transfers_respect_balances_and_allowances() {
+        let vm = TestVM::default();
+        let mut token = deploy(&vm);
+        vm.set_sender(SWORDS);
+        token.mint(PLAYER, U256::from(10)).unwrap();
+
+        vm.set_sender(PLAYER);
+        assert!(token.transfer(FRIEND, U256::from(4)).unwrap());
+        assert!(matches!(
+            token.transfer(FRIEND, U256::from(7)),
+            Err(GoldError::InsufficientBalance(_))
+        ));
+        token.approve(FRIEND, U256::from(3));
+
+        vm.set_sender(FRIEND);
+        assert!(matches!(
+            token.transfer_from(PLAYER, FRIEND, U256::from(4)),
+            Err(GoldError::InsufficientAllowance(_))
+        ));
+        assert!(token.transfer_from(PLAYER, FRIEND, U256::from(3)).unwrap());
+        assert_eq!(token.balance_of(PLAYER), U256::from(3));
+        assert_eq!(token.balance_of(FRIEND), U256::from(7));
+        assert_eq!(token.allowance(PLAYER, FRIEND), U256::ZERO);
+    }
+}
@@ -0,0 +1,30 @@
+#!/usr/bin/env bash
+# Deploys the sword contract and the gold token, then points the sword
+# contract at the token so every recorded pickup mints gold.
+#
+# Requires cargo-stylus (with constructor support) and Foundry's `cast`.
+# Defaults target the local Nitro dev node used in the top-level README.
+set -euo pipefail
+
+RPC_URL="${RPC_URL:-http://localhost:8547}"
+PRIVATE_KEY="${PRIVATE_KEY:-0xb6b15c8cb491557369f3c7d2c287b053eb229daa9c22138887752191c9520659}"
+
+cd "$(dirname "$0")/.."
+
+deployed_address() {
+    grep -oiE 'deployed code at address:? *(0x[0-9a-f]{40})' | grep -oiE '0x[0-9a-f]{40}' | tail -n 1
+}
+
+echo "Deploying sword contract..."
+SWORDS=$(cargo stylus deploy --endpoint="$RPC_URL" --private-key="$PRIVATE_KEY" --no-verify | tee /dev/stderr | deployed_address)
+
+echo "Deploying gold token with minter $SWORDS..."
+GOLD=$(cd gold && cargo stylus deploy --endpoint="$RPC_URL" --private-key="$PRIVATE_KEY" --no-verify \
+    --constructor-args "$SWORDS" | tee /dev/stderr | deployed_address)
+
+echo "Linking gold token to sword contract..."
+cast send --rpc-url "$RPC_URL" --private-key "$PRIVATE_KEY" "$SWORDS" "setGoldToken(address)" "$GOLD"
+
+echo
+echo "STYLUS_CONTRACT_ADDRESS=$SWORDS"
+echo "GOLD_TOKEN_ADDRESS=$GOLD"
@@ -0,0 +1,80 @@
+//! ERC-2771 meta-transactions. A trusted forwarder appends the original
+//! sender's 20-byte address to the calldata. The SDK router rejects trailing
+//! calldata, so this entrypoint removes the suffix before routing and records
+//! the sender for [`Counter::msg_sender`](crate::Counter).
+
+use alloc::vec::Vec;
+use core::cell::Cell;
+
+use stylus_sdk::{
+    abi::router_entrypoint, alloy_primitives::Address, host::VM, prelude::*, ArbResult,
+};
+
+use crate::Counter;
+
+/// Length of the sender suffix appended by the forwarder.
+const SENDER_SUFFIX_LENGTH: usize = 20;
+
+#[cfg(target_arch = "wasm32")]
+fn with_sender_slot<R>(f: impl FnOnce(&Cell<Option<Address>>) -> R) -> R {
+    struct SenderSlot(Cell<Option<Address>>);
+    // Contracts run single-threaded, and each call gets a fresh instance.
+    unsafe impl Sync for SenderSlot {}
+    static FORWARDED_SENDER: SenderSlot = SenderSlot(Cell::new(None));
+    f(&FORWARDED_SENDER.0)
+}
+
+/// Native tests run in parallel, so each thread gets its own slot.
+#[cfg(not(target_arch = "wasm32"))]
+fn with_sender_slot<R>(f: impl FnOnce(&Cell<Option<Address>>) -> R) -> R {
+    extern crate std;
+    std::thread_local! {
+        static FORWARDED_SENDER: Cell<Option<Address>> = const { Cell::new(None) };
+    }
+    FORWARDED_SENDER.with(f)
+}
+
+/// The sender the trusted forwarder vouched for in the current call, if any.
+pub fn forwarded_sender() -> Option<Address> {
+    with_sender_slot(|slot| slot.get())
+}
+
+/// Splits `calldata` into the forwarded call and its original sender.
+pub fn split_forwarded(calldata: &[u8]) -> Option<(&[u8], Address)> {
+    // A selector must remain after the suffix is removed.
+    let split = calldata.len().checked_sub(SENDER_SUFFIX_LENGTH).filter(|&split| split >= 4)?;
+    let (call, sender) = calldata.split_at(split);
+    Some((call, Address::from_slice(sender)))
+}
+
+/// Exported as `user_entrypoint` in contract builds; public so native tests
+/// can drive calls through it.
+#[entrypoint]
+pub fn forwarding_entrypoint(mut input: Vec<u8>, host: VM) -> ArbResult {
+    let storage = unsafe { <Counter as StorageType>::new(Default::default(), 0, host.clone()) };
+    let mut sender = None;
+    if storage.vm().msg_sender() == storage.trusted_forwarder() {
+        if let Some((call, original)) = split_forwarded(&input) {
+            sender = Some(original);
+            input.truncate(call.len());
+        }
+    }
+
+    with_sender_slot(|slot| slot.set(sender));
+    let result = router_entrypoint::<Counter, Counter>(input, host);
+    with_sender_slot(|slot| slot.set(None));
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn short_calldata_is_not_split() {
+        let player = Address::repeat_byte(0x01);
+        assert_eq!(split_forwarded(&[0u8; 23]), None);
+        let calldata = [[1u8; 4].as_slice(), player.as_slice()].concat();
+        assert_eq!(split_forwarded(&calldata), Some(([1u8; 4].as_slice(), player)));
+    }
+}
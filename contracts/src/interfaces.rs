@@ -0,0 +1,113 @@
+//! ERC-165 interface ids, one per feature set. Each id is the XOR of the
+//! selectors in the set, so clients can compute it from the ABI alone.
+
+use stylus_sdk::keccak_const::Keccak256;
+
+pub const ERC165: [u8; 4] = interface_id(&["supportsInterface(bytes4)"]);
+
+pub const SWORDS: [u8; 4] = interface_id(&[
+    "getSwordCounts()",
+    "incrementSword(uint256)",
+    "balanceOf(address,uint256)",
+    "getPlayerSwordCounts(address)",
+    "transferSwords(address,uint256,uint256)",
+    "getLootTable()",
+]);
+
+pub const NAMES: [u8; 4] = interface_id(&["setName(string)", "nameOf(address)", "addressOf(string)"]);
+
+pub const PICKUP_CLAIMS: [u8; 4] = interface_id(&[
+    "nonces(address)",
+    "DOMAIN_SEPARATOR()",
+    "claimPickup(address,uint256,uint256,uint256,uint256,bytes)",
+    "isSigner(address)",
+    "signerThreshold()",
+]);
+
+pub const KILLS: [u8; 4] = interface_id(&[
+    "recordKills(uint256)",
+    "claimKills(address,uint256,uint256,uint256,bytes)",
+    "killsOf(address)",
+    "totalKills()",
+    "pickupsOf(address)",
+    "killsPerSword(address)",
+    "worldKillsPerSword()",
+]);
+
+pub const SETTLEMENT: [u8; 4] = interface_id(&[
+    "settlementRoot()",
+    "settlementEpoch()",
+    "postRoot(bytes32)",
+    "getSettledSwordCounts(address)",
+    "claim(bytes32[],uint256[4])",
+]);
+
+pub const TOURNAMENTS: [u8; 4] = interface_id(&[
+    "createTournament(uint256,uint256,uint256,uint256[])",
+    "tournamentCount()",
+    "getTournament(uint256)",
+    "getTournamentPayouts(uint256)",
+    "getTournamentStandings(uint256)",
+    "tournamentScore(uint256,address)",
+    "currentTournament(address)",
+    "joinTournament(uint256)",
+    "finalizeTournament(uint256)",
+    "prizeBalance(address)",
+    "withdrawPrizes()",
+]);
+
+pub const SKINS: [u8; 4] = interface_id(&[
+    "ownsSkin(address,uint256)",
+    "getOwnedSkins(address)",
+    "grantSkin(address,uint256)",
+    "equippedSkin(address)",
+    "equipSkin(uint256)",
+]);
+
+pub const DAILY_REWARD: [u8; 4] = interface_id(&[
+    "getDailyReward()",
+    "setDailyReward(uint256,uint256)",
+    "nextDailyClaim(address)",
+    "claimDaily()",
+]);
+
+pub const GUILDS: [u8; 4] = interface_id(&[
+    "createGuild(string)",
+    "joinGuild(uint256)",
+    "leaveGuild()",
+    "guildCount()",
+    "guildOf(address)",
+    "guildByTag(string)",
+    "getGuild(uint256)",
+    "getGuildRoster(uint256)",
+    "getGuildLeaderboard(uint256)",
+]);
+
+pub const SUPPORTED: [[u8; 4]; 10] =
+    [ERC165, SWORDS, NAMES, PICKUP_CLAIMS, KILLS, SETTLEMENT, TOURNAMENTS, SKINS, DAILY_REWARD, GUILDS];
+
+const fn interface_id(signatures: &[&str]) -> [u8; 4] {
+    let mut id = [0u8; 4];
+    let mut index = 0;
+    while index < signatures.len() {
+        let hash = Keccak256::new().update(signatures[index].as_bytes()).finalize();
+        let mut byte = 0;
+        while byte < 4 {
+            id[byte] ^= hash[byte];
+            byte += 1;
+        }
+        index += 1;
+    }
+    id
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn erc165_id_matches_the_standard() {
+        assert_eq!(ERC165, [0x01, 0xff, 0xc9, 0xa7]);
+        assert_eq!(NAMES, interface_id(&["nameOf(address)", "addressOf(string)", "setName(string)"]));
+    }
+}
@@ -4,31 +4,1206 @@
 #[macro_use]
 extern crate alloc;
 
-use alloc::vec::Vec;
-use stylus_sdk::{alloy_primitives::U256, prelude::*};
+mod forwarder;
+pub mod interfaces;
+mod merkle;
+mod metadata;
+mod signatures;
+mod tournament;
 
+use alloc::{string::String, vec::Vec};
+use alloy_sol_types::sol;
+use stylus_sdk::{
+    abi::Bytes,
+    alloy_primitives::{Address, FixedBytes, B256, U256},
+    prelude::*,
+};
+
+pub const RED: u8 = 0;
+pub const GREEN: u8 = 1;
+pub const BLUE: u8 = 2;
+pub const NORMAL: u8 = 3;
+pub const COLOR_COUNT: usize = 4;
+
+/// Weights written by the constructor, indexed by color: red, green, blue, normal.
+const DEFAULT_LOOT_WEIGHTS: [u64; COLOR_COUNT] = [3, 3, 3, 1];
+
+/// Swords granted by `claim_daily` until the owner changes them, indexed by color.
+const DEFAULT_DAILY_REWARD: [u64; COLOR_COUNT] = [1, 1, 1, 0];
+
+/// Players may claim the daily reward once per this many seconds.
+pub const DAILY_COOLDOWN: u64 = 24 * 60 * 60;
+
+/// Gold minted for every recorded pickup: one whole token at 18 decimals.
+pub const GOLD_PER_PICKUP: U256 = U256::from_limbs([1_000_000_000_000_000_000, 0, 0, 0]);
+
+/// Gold minted for every recorded kill: a tenth of a token.
+pub const GOLD_PER_KILL: U256 = U256::from_limbs([100_000_000_000_000_000, 0, 0, 0]);
+
+/// `killsPerSword` views are fixed-point with this many decimals.
+pub const KILL_RATIO_DECIMALS: u8 = 18;
+
+pub const NAME_MIN_LENGTH: usize = 3;
+pub const NAME_MAX_LENGTH: usize = 16;
+
+pub const GUILD_TAG_MIN_LENGTH: usize = 2;
+pub const GUILD_TAG_MAX_LENGTH: usize = 5;
+/// Bounds the roster scan in `leaveGuild`.
+pub const MAX_GUILD_MEMBERS: usize = 50;
+
+/// The skin every player starts with and can always equip.
+pub const DEFAULT_SKIN: U256 = U256::ZERO;
+
+// The entrypoint lives in `forwarder` so it can unwrap ERC-2771 calls.
 sol_storage! {
-    #[entrypoint]
     pub struct Counter {
         uint256 red_swords;
         uint256 green_swords;
         uint256 blue_swords;
+        uint256 normal_swords;
+        address owner;
+        uint256[4] loot_weights;
+        mapping(address => string) names;
+        mapping(bytes32 => address) name_owners;
+        mapping(address => mapping(uint256 => uint256)) balances;
+        address gold_token;
+        mapping(address => uint256) nonces;
+        address trusted_forwarder;
+        address settlement_server;
+        bytes32 settlement_root;
+        uint256 settlement_epoch;
+        mapping(address => mapping(uint256 => uint256)) settled_swords;
+        uint256 tournament_count;
+        mapping(uint256 => Tournament) tournaments;
+        mapping(address => uint256) current_tournament;
+        mapping(address => uint256) prizes;
+        mapping(address => bool) signers;
+        address[] signer_list;
+        uint256 signer_threshold;
+        mapping(address => mapping(uint256 => bool)) owned_skins;
+        mapping(address => uint256[]) skin_lists;
+        mapping(address => uint256) equipped_skins;
+        uint256[4] daily_reward;
+        mapping(address => uint256) last_daily_claims;
+        uint256 total_kills;
+        mapping(address => uint256) kills;
+        mapping(address => uint256) pickups;
+        uint256 guild_count;
+        mapping(uint256 => Guild) guilds;
+        mapping(bytes32 => uint256) guild_tags;
+        mapping(address => uint256) guild_of;
+    }
+
+    /// Guild ids start at 1 so that 0 means "none".
+    pub struct Guild {
+        string tag;
+        address[] members;
+        uint256 total_swords;
+    }
+
+    /// Tournament ids start at 1 so that 0 means "none".
+    pub struct Tournament {
+        uint256 entry_fee;
+        uint256 start_time;
+        uint256 end_time;
+        uint256 prize_pool;
+        uint256[] payout_percentages;
+        address[] entrants;
+        mapping(address => uint256) scores;
+        bool finalized;
+    }
+}
+
+unsafe impl stylus_sdk::stylus_core::storage::TopLevelStorage for Counter {}
+
+#[doc(hidden)]
+pub use forwarder::forwarding_entrypoint;
+
+#[cfg(feature = "export-abi")]
+pub fn print_from_args() {
+    stylus_sdk::abi::export::print_from_args::<Counter>();
+}
+
+sol_interface! {
+    interface IGoldToken {
+        function mint(address to, uint256 amount) external;
     }
 }
 
+sol! {
+    event LootWeightSet(uint256 indexed color, uint256 weight);
+    event NameSet(address indexed player, string name);
+    event SwordsTransferred(address indexed from, address indexed to, uint256 indexed color, uint256 amount);
+    event GoldTokenSet(address token);
+    event PickupClaimed(address indexed player, uint256 indexed color, uint256 amount, uint256 nonce);
+    event TrustedForwarderSet(address forwarder);
+    event SettlementServerSet(address server);
+    event RootPosted(bytes32 indexed root, uint256 epoch);
+    event SessionClaimed(address indexed player, uint256 epoch);
+    event TournamentCreated(uint256 indexed id, uint256 entry_fee, uint256 start_time, uint256 end_time);
+    event TournamentJoined(uint256 indexed id, address indexed player);
+    event TournamentFinalized(uint256 indexed id, uint256 prize_pool);
+    event PrizeAwarded(uint256 indexed id, address indexed player, uint256 rank, uint256 amount);
+    event PrizeWithdrawn(address indexed player, uint256 amount);
+    event SignerAdded(address indexed signer);
+    event SignerRemoved(address indexed signer);
+    event SignerThresholdSet(uint256 threshold);
+    event SkinGranted(address indexed player, uint256 indexed skin);
+    event SkinEquipped(address indexed player, uint256 indexed skin);
+    event DailyRewardSet(uint256 indexed color, uint256 amount);
+    event DailyClaimed(address indexed player, uint256 next_claim_time);
+    event KillsRecorded(address indexed player, uint256 count);
+    event GuildCreated(uint256 indexed id, string tag);
+    event GuildJoined(uint256 indexed id, address indexed player);
+    event GuildLeft(uint256 indexed id, address indexed player);
+    event SwordFound(address indexed player, uint256 indexed color, uint256 amount);
+
+    #[derive(Debug)]
+    error NotOwner(address caller);
+    #[derive(Debug)]
+    error InvalidColor(uint256 color);
+    #[derive(Debug)]
+    error InvalidNameLength(uint256 length);
+    #[derive(Debug)]
+    error InvalidNameCharacter(uint256 index);
+    #[derive(Debug)]
+    error NameTaken(string name, address owner);
+    #[derive(Debug)]
+    error InvalidTokenId(uint256 id);
+    #[derive(Debug)]
+    error InsufficientSwords(uint256 color, uint256 balance, uint256 requested);
+    #[derive(Debug)]
+    error InvalidRecipient(address to);
+    #[derive(Debug)]
+    error GoldMintFailed(address token);
+    #[derive(Debug)]
+    error ClaimExpired(uint256 deadline, uint256 timestamp);
+    #[derive(Debug)]
+    error InvalidNonce(uint256 expected, uint256 provided);
+    #[derive(Debug)]
+    error InvalidSignature();
+    #[derive(Debug)]
+    error NotSettlementServer(address caller);
+    #[derive(Debug)]
+    error InvalidProof();
+    #[derive(Debug)]
+    error NothingToClaim();
+    #[derive(Debug)]
+    error InvalidTournamentWindow(uint256 start_time, uint256 end_time);
+    #[derive(Debug)]
+    error InvalidPayouts(uint256 places, uint256 total_percentage);
+    #[derive(Debug)]
+    error UnknownTournament(uint256 id);
+    #[derive(Debug)]
+    error TournamentClosed(uint256 id, uint256 end_time);
+    #[derive(Debug)]
+    error TournamentFull(uint256 id);
+    #[derive(Debug)]
+    error WrongEntryFee(uint256 expected, uint256 provided);
+    #[derive(Debug)]
+    error AlreadyInTournament(uint256 id);
+    #[derive(Debug)]
+    error TournamentNotOver(uint256 id, uint256 end_time);
+    #[derive(Debug)]
+    error TournamentAlreadyFinalized(uint256 id);
+    #[derive(Debug)]
+    error PrizeTransferFailed(address player, uint256 amount);
+    #[derive(Debug)]
+    error InsufficientSignatures(uint256 required, uint256 valid);
+    #[derive(Debug)]
+    error InvalidThreshold(uint256 threshold, uint256 signers);
+    #[derive(Debug)]
+    error SignerAlreadyAdded(address signer);
+    #[derive(Debug)]
+    error InvalidSigner(address signer);
+    #[derive(Debug)]
+    error UnknownSigner(address signer);
+    #[derive(Debug)]
+    error SkinAlreadyOwned(address player, uint256 skin);
+    #[derive(Debug)]
+    error SkinNotOwned(address player, uint256 skin);
+    #[derive(Debug)]
+    error DailyCooldown(uint256 next_claim_time, uint256 remaining);
+    #[derive(Debug)]
+    error InvalidGuildTag(string tag);
+    #[derive(Debug)]
+    error GuildTagTaken(string tag, uint256 id);
+    #[derive(Debug)]
+    error UnknownGuild(uint256 id);
+    #[derive(Debug)]
+    error AlreadyInGuild(uint256 id);
+    #[derive(Debug)]
+    error NotInGuild();
+    #[derive(Debug)]
+    error GuildFull(uint256 id);
+}
+
+#[derive(SolidityError, Debug)]
+pub enum CounterError {
+    NotOwner(NotOwner),
+    InvalidColor(InvalidColor),
+    InvalidNameLength(InvalidNameLength),
+    InvalidNameCharacter(InvalidNameCharacter),
+    NameTaken(NameTaken),
+    InvalidTokenId(InvalidTokenId),
+    InsufficientSwords(InsufficientSwords),
+    InvalidRecipient(InvalidRecipient),
+    GoldMintFailed(GoldMintFailed),
+    ClaimExpired(ClaimExpired),
+    InvalidNonce(InvalidNonce),
+    InvalidSignature(InvalidSignature),
+    NotSettlementServer(NotSettlementServer),
+    InvalidProof(InvalidProof),
+    NothingToClaim(NothingToClaim),
+    InvalidTournamentWindow(InvalidTournamentWindow),
+    InvalidPayouts(InvalidPayouts),
+    UnknownTournament(UnknownTournament),
+    TournamentClosed(TournamentClosed),
+    TournamentFull(TournamentFull),
+    WrongEntryFee(WrongEntryFee),
+    AlreadyInTournament(AlreadyInTournament),
+    TournamentNotOver(TournamentNotOver),
+    TournamentAlreadyFinalized(TournamentAlreadyFinalized),
+    PrizeTransferFailed(PrizeTransferFailed),
+    InsufficientSignatures(InsufficientSignatures),
+    InvalidThreshold(InvalidThreshold),
+    SignerAlreadyAdded(SignerAlreadyAdded),
+    InvalidSigner(InvalidSigner),
+    UnknownSigner(UnknownSigner),
+    SkinAlreadyOwned(SkinAlreadyOwned),
+    SkinNotOwned(SkinNotOwned),
+    DailyCooldown(DailyCooldown),
+    InvalidGuildTag(InvalidGuildTag),
+    GuildTagTaken(GuildTagTaken),
+    UnknownGuild(UnknownGuild),
+    AlreadyInGuild(AlreadyInGuild),
+    NotInGuild(NotInGuild),
+    GuildFull(GuildFull),
+}
+
 #[public]
 impl Counter {
-    pub fn get_sword_counts(&self) -> (U256, U256, U256) {
-        (self.red_swords.get(), self.green_swords.get(), self.blue_swords.get())
+    #[constructor]
+    pub fn constructor(&mut self) {
+        // Deployments go through the StylusDeployer contract, so the deploying
+        // account is the transaction origin rather than the message sender.
+        self.owner.set(self.vm().tx_origin());
+        for (color, weight) in DEFAULT_LOOT_WEIGHTS.iter().enumerate() {
+            self.loot_weights.setter(color).unwrap().set(U256::from(*weight));
+        }
+        for (color, amount) in DEFAULT_DAILY_REWARD.iter().enumerate() {
+            self.daily_reward.setter(color).unwrap().set(U256::from(*amount));
+        }
     }
 
-    pub fn increment_sword(&mut self, color: U256) {
-        if color == U256::from(0) {
-            self.red_swords.set(self.red_swords.get() + U256::from(1));
-        } else if color == U256::from(1) {
-            self.green_swords.set(self.green_swords.get() + U256::from(1));
-        } else if color == U256::from(2) {
-            self.blue_swords.set(self.blue_swords.get() + U256::from(1));
+    pub fn owner(&self) -> Address {
+        self.owner.get()
+    }
+
+    /// ERC-165. Ids for each feature set are listed in [`interfaces`].
+    pub fn supports_interface(&self, interface_id: FixedBytes<4>) -> bool {
+        interfaces::SUPPORTED.contains(&interface_id.0)
+    }
+
+    /// The crate version this contract was built from, as `major.minor.patch`.
+    pub fn version(&self) -> String {
+        env!("CARGO_PKG_VERSION").into()
+    }
+
+    pub fn get_sword_counts(&self) -> (U256, U256, U256, U256) {
+        (
+            self.red_swords.get(),
+            self.green_swords.get(),
+            self.blue_swords.get(),
+            self.normal_swords.get(),
+        )
+    }
+
+    /// Records a pickup for the caller without a claim. Only open while no
+    /// signer threshold is set; after that every pickup goes through `claimPickup`.
+    pub fn increment_sword(&mut self, color: U256) -> Result<(), CounterError> {
+        self.check_unattested()?;
+        if color >= U256::from(COLOR_COUNT) {
+            return Ok(());
         }
+        let player = self.msg_sender();
+        self.record_pickup(player, color, U256::from(1))
+    }
+
+    pub fn nonces(&self, player: Address) -> U256 {
+        self.nonces.get(player)
+    }
+
+    #[selector(name = "DOMAIN_SEPARATOR")]
+    pub fn domain_separator(&self) -> B256 {
+        signatures::domain_separator(self.vm(), self.vm().chain_id(), self.vm().contract_address())
+    }
+
+    /// Records a pickup attested as EIP-712 `Pickup` typed data, so anyone can
+    /// submit it on the player's behalf. `signatures` holds concatenated 65-byte
+    /// signatures: at least `signerThreshold()` from distinct registered
+    /// signers, or the player's own while no threshold is set. Each nonce is
+    /// usable once and claims must be submitted before `deadline`.
+    pub fn claim_pickup(
+        &mut self,
+        player: Address,
+        color: U256,
+        amount: U256,
+        nonce: U256,
+        deadline: U256,
+        signatures: Bytes,
+    ) -> Result<(), CounterError> {
+        Self::color_index(color)?;
+        self.check_claim(player, nonce, deadline)?;
+        let struct_hash = signatures::pickup_struct_hash(self.vm(), player, color, amount, nonce, deadline);
+        let digest = signatures::typed_data_digest(self.vm(), self.domain_separator(), struct_hash);
+        self.check_attestation(player, digest, &signatures)?;
+
+        self.nonces.insert(player, nonce + U256::from(1));
+        self.record_pickup(player, color, amount)?;
+        log(self.vm(), PickupClaimed { player, color, amount, nonce });
+        Ok(())
+    }
+
+    /// Records a batch of kills for the caller, as `incrementSword` does for
+    /// pickups, and closes with it once a signer threshold is set.
+    pub fn record_kills(&mut self, count: U256) -> Result<(), CounterError> {
+        self.check_unattested()?;
+        let player = self.msg_sender();
+        self.add_kills(player, count)
+    }
+
+    /// Records kills attested as EIP-712 `Kills(address player,uint256 count,
+    /// uint256 nonce,uint256 deadline)` typed data. Signatures are checked
+    /// exactly as for `claimPickup`, and both claims draw from `nonces(player)`.
+    pub fn claim_kills(
+        &mut self,
+        player: Address,
+        count: U256,
+        nonce: U256,
+        deadline: U256,
+        signatures: Bytes,
+    ) -> Result<(), CounterError> {
+        self.check_claim(player, nonce, deadline)?;
+        let struct_hash = signatures::kills_struct_hash(self.vm(), player, count, nonce, deadline);
+        let digest = signatures::typed_data_digest(self.vm(), self.domain_separator(), struct_hash);
+        self.check_attestation(player, digest, &signatures)?;
+
+        self.nonces.insert(player, nonce + U256::from(1));
+        self.add_kills(player, count)
+    }
+
+    pub fn kills_of(&self, player: Address) -> U256 {
+        self.kills.get(player)
+    }
+
+    pub fn total_kills(&self) -> U256 {
+        self.total_kills.get()
+    }
+
+    /// Swords the player has picked up, ignoring transfers, gifts and daily rewards.
+    pub fn pickups_of(&self, player: Address) -> U256 {
+        self.pickups.get(player)
+    }
+
+    pub fn kills_per_sword(&self, player: Address) -> U256 {
+        Self::kill_ratio(self.kills.get(player), self.pickups.get(player))
+    }
+
+    pub fn world_kills_per_sword(&self) -> U256 {
+        let (red, green, blue, normal) = self.get_sword_counts();
+        Self::kill_ratio(self.total_kills.get(), red + green + blue + normal)
+    }
+
+    /// Creates a guild with the caller as its first member. Tags are 2 to 5
+    /// uppercase letters or digits and unique across guilds.
+    pub fn create_guild(&mut self, tag: String) -> Result<U256, CounterError> {
+        Self::validate_guild_tag(&tag)?;
+        let player = self.msg_sender();
+        self.not_in_guild(player)?;
+        let key = self.name_key(&tag);
+        let taken = self.guild_tags.get(key);
+        if taken != U256::ZERO {
+            return Err(CounterError::GuildTagTaken(GuildTagTaken { tag, id: taken }));
+        }
+
+        let id = self.guild_count.get() + U256::from(1);
+        self.guild_count.set(id);
+        self.guild_tags.insert(key, id);
+        self.guilds.setter(id).tag.set_str(&tag);
+        log(self.vm(), GuildCreated { id, tag });
+        self.add_guild_member(id, player);
+        Ok(id)
+    }
+
+    pub fn join_guild(&mut self, id: U256) -> Result<(), CounterError> {
+        self.guild_exists(id)?;
+        let player = self.msg_sender();
+        self.not_in_guild(player)?;
+        if self.guilds.getter(id).members.len() >= MAX_GUILD_MEMBERS {
+            return Err(CounterError::GuildFull(GuildFull { id }));
+        }
+        self.add_guild_member(id, player);
+        Ok(())
+    }
+
+    /// Swords the player found while a member stay in the guild's total.
+    pub fn leave_guild(&mut self) -> Result<(), CounterError> {
+        let player = self.msg_sender();
+        let id = self.guild_of.get(player);
+        if id == U256::ZERO {
+            return Err(CounterError::NotInGuild(NotInGuild {}));
+        }
+
+        let roster = self.get_guild_roster(id)?;
+        let index = roster.iter().position(|&member| member == player).unwrap();
+        let mut guild = self.guilds.setter(id);
+        guild.members.setter(index).unwrap().set(roster[roster.len() - 1]);
+        guild.members.erase_last();
+        self.guild_of.delete(player);
+        log(self.vm(), GuildLeft { id, player });
+        Ok(())
+    }
+
+    pub fn guild_count(&self) -> U256 {
+        self.guild_count.get()
+    }
+
+    /// The player's guild id, or 0 if they are not in one.
+    pub fn guild_of(&self, player: Address) -> U256 {
+        self.guild_of.get(player)
+    }
+
+    pub fn guild_by_tag(&self, tag: String) -> U256 {
+        self.guild_tags.get(self.name_key(&tag))
+    }
+
+    /// Returns the tag, member count and collective sword total.
+    pub fn get_guild(&self, id: U256) -> Result<(String, U256, U256), CounterError> {
+        self.guild_exists(id)?;
+        let guild = self.guilds.getter(id);
+        Ok((guild.tag.get_string(), U256::from(guild.members.len()), guild.total_swords.get()))
+    }
+
+    pub fn get_guild_roster(&self, id: U256) -> Result<Vec<Address>, CounterError> {
+        self.guild_exists(id)?;
+        let guild = self.guilds.getter(id);
+        Ok((0..guild.members.len()).map(|index| guild.members.get(index).unwrap()).collect())
+    }
+
+    /// The top `limit` guild ids and their totals, best first. Every guild
+    /// is scanned, so this is meant for `eth_call` rather than transactions.
+    pub fn get_guild_leaderboard(&self, limit: U256) -> (Vec<U256>, Vec<U256>) {
+        let count = self.guild_count.get().to::<usize>();
+        let totals: Vec<U256> = (1..=count).map(|id| self.guilds.getter(U256::from(id)).total_swords.get()).collect();
+        let limit = limit.min(U256::from(count)).to::<usize>();
+        tournament::rank(&totals)
+            .into_iter()
+            .take(limit)
+            .map(|index| (U256::from(index + 1), totals[index]))
+            .unzip()
+    }
+
+    pub fn settlement_server(&self) -> Address {
+        self.settlement_server.get()
+    }
+
+    pub fn set_settlement_server(&mut self, server: Address) -> Result<(), CounterError> {
+        self.only_owner()?;
+        self.settlement_server.set(server);
+        log(self.vm(), SettlementServerSet { server });
+        Ok(())
+    }
+
+    pub fn settlement_root(&self) -> B256 {
+        self.settlement_root.get()
+    }
+
+    pub fn settlement_epoch(&self) -> U256 {
+        self.settlement_epoch.get()
+    }
+
+    /// Each root covers every player's cumulative counts, so a new root
+    /// replaces the last without stranding unclaimed swords.
+    pub fn post_root(&mut self, root: B256) -> Result<(), CounterError> {
+        let caller = self.msg_sender();
+        if caller != self.settlement_server.get() {
+            return Err(CounterError::NotSettlementServer(NotSettlementServer { caller }));
+        }
+        let epoch = self.settlement_epoch.get() + U256::from(1);
+        self.settlement_root.set(root);
+        self.settlement_epoch.set(epoch);
+        log(self.vm(), RootPosted { root, epoch });
+        Ok(())
+    }
+
+    pub fn get_settled_sword_counts(&self, player: Address) -> (U256, U256, U256, U256) {
+        let settled = self.settled_swords.get(player);
+        (
+            settled.get(U256::from(RED)),
+            settled.get(U256::from(GREEN)),
+            settled.get(U256::from(BLUE)),
+            settled.get(U256::from(NORMAL)),
+        )
+    }
+
+    /// Credits the swords in `color_counts` not already claimed under an
+    /// earlier root.
+    pub fn claim(&mut self, proof: Vec<B256>, color_counts: [U256; COLOR_COUNT]) -> Result<(), CounterError> {
+        let player = self.msg_sender();
+        let leaf = merkle::session_leaf(self.vm(), player, color_counts);
+        if !merkle::verify(self.vm(), &proof, self.settlement_root.get(), leaf) {
+            return Err(CounterError::InvalidProof(InvalidProof {}));
+        }
+
+        let mut claimed_any = false;
+        for (color, &count) in color_counts.iter().enumerate() {
+            let color = U256::from(color);
+            let settled = self.settled_swords.get(player).get(color);
+            if count > settled {
+                self.settled_swords.setter(player).setter(color).set(count);
+                // Settled swords come from earlier play, so they never score in the running tournament.
+                self.credit_pickup(player, color, count - settled)?;
+                claimed_any = true;
+            }
+        }
+        if !claimed_any {
+            return Err(CounterError::NothingToClaim(NothingToClaim {}));
+        }
+        log(self.vm(), SessionClaimed { player, epoch: self.settlement_epoch.get() });
+        Ok(())
+    }
+
+    /// `payout_percentages` lists whole percentages from first place down and
+    /// must add up to 100. Returns the new tournament's id.
+    pub fn create_tournament(
+        &mut self,
+        entry_fee: U256,
+        start_time: U256,
+        end_time: U256,
+        payout_percentages: Vec<U256>,
+    ) -> Result<U256, CounterError> {
+        self.only_owner()?;
+        if start_time >= end_time || end_time <= U256::from(self.vm().block_timestamp()) {
+            return Err(CounterError::InvalidTournamentWindow(InvalidTournamentWindow { start_time, end_time }));
+        }
+        let total_percentage = payout_percentages.iter().fold(U256::ZERO, |sum, percentage| sum + percentage);
+        let places = payout_percentages.len();
+        if places == 0 || places > tournament::MAX_PAID_PLACES || total_percentage != U256::from(100) {
+            let places = U256::from(places);
+            return Err(CounterError::InvalidPayouts(InvalidPayouts { places, total_percentage }));
+        }
+
+        let id = self.tournament_count.get() + U256::from(1);
+        self.tournament_count.set(id);
+        let mut created = self.tournaments.setter(id);
+        created.entry_fee.set(entry_fee);
+        created.start_time.set(start_time);
+        created.end_time.set(end_time);
+        for percentage in payout_percentages {
+            created.payout_percentages.push(percentage);
+        }
+        log(self.vm(), TournamentCreated { id, entry_fee, start_time, end_time });
+        Ok(id)
+    }
+
+    pub fn tournament_count(&self) -> U256 {
+        self.tournament_count.get()
+    }
+
+    /// Entry fee, start time, end time, prize pool, entrant count and whether
+    /// prizes have been awarded.
+    pub fn get_tournament(&self, id: U256) -> Result<(U256, U256, U256, U256, U256, bool), CounterError> {
+        self.tournament_exists(id)?;
+        let tournament = self.tournaments.getter(id);
+        Ok((
+            tournament.entry_fee.get(),
+            tournament.start_time.get(),
+            tournament.end_time.get(),
+            tournament.prize_pool.get(),
+            U256::from(tournament.entrants.len()),
+            tournament.finalized.get(),
+        ))
+    }
+
+    pub fn get_tournament_payouts(&self, id: U256) -> Result<Vec<U256>, CounterError> {
+        self.tournament_exists(id)?;
+        let payouts = &self.tournaments.getter(id).payout_percentages;
+        Ok((0..payouts.len()).map(|place| payouts.get(place).unwrap()).collect())
+    }
+
+    /// Entrants and their scores, best first.
+    pub fn get_tournament_standings(&self, id: U256) -> Result<(Vec<Address>, Vec<U256>), CounterError> {
+        self.tournament_exists(id)?;
+        let (entrants, scores) = self.tournament_entries(id);
+        Ok(tournament::rank(&scores).into_iter().map(|index| (entrants[index], scores[index])).unzip())
+    }
+
+    /// Swords `player` picked up while the tournament was running.
+    pub fn tournament_score(&self, id: U256, player: Address) -> U256 {
+        self.tournaments.getter(id).scores.get(player)
+    }
+
+    /// The tournament `player` last joined, or 0.
+    pub fn current_tournament(&self, player: Address) -> U256 {
+        self.current_tournament.get(player)
+    }
+
+    /// Entry is open until the tournament ends; a player can only be in one
+    /// unfinished tournament at a time.
+    #[payable]
+    pub fn join_tournament(&mut self, id: U256) -> Result<(), CounterError> {
+        self.tournament_exists(id)?;
+        let player = self.msg_sender();
+        let now = U256::from(self.vm().block_timestamp());
+        let current = self.current_tournament.get(player);
+        if current != U256::ZERO && now < self.tournaments.getter(current).end_time.get() {
+            return Err(CounterError::AlreadyInTournament(AlreadyInTournament { id: current }));
+        }
+
+        let provided = self.vm().msg_value();
+        let mut joined = self.tournaments.setter(id);
+        let end_time = joined.end_time.get();
+        if now >= end_time {
+            return Err(CounterError::TournamentClosed(TournamentClosed { id, end_time }));
+        }
+        if joined.entrants.len() >= tournament::MAX_ENTRANTS {
+            return Err(CounterError::TournamentFull(TournamentFull { id }));
+        }
+        let expected = joined.entry_fee.get();
+        if provided != expected {
+            return Err(CounterError::WrongEntryFee(WrongEntryFee { expected, provided }));
+        }
+
+        joined.entrants.push(player);
+        let prize_pool = joined.prize_pool.get();
+        joined.prize_pool.set(prize_pool + provided);
+        self.current_tournament.insert(player, id);
+        log(self.vm(), TournamentJoined { id, player });
+        Ok(())
+    }
+
+    /// Anyone can settle a tournament once it ends. Prizes are credited and
+    /// withdrawn separately, so one failing recipient cannot block the rest.
+    pub fn finalize_tournament(&mut self, id: U256) -> Result<(), CounterError> {
+        self.tournament_exists(id)?;
+        let end_time = self.tournaments.getter(id).end_time.get();
+        if U256::from(self.vm().block_timestamp()) < end_time {
+            return Err(CounterError::TournamentNotOver(TournamentNotOver { id, end_time }));
+        }
+        if self.tournaments.getter(id).finalized.get() {
+            return Err(CounterError::TournamentAlreadyFinalized(TournamentAlreadyFinalized { id }));
+        }
+
+        let (entrants, scores) = self.tournament_entries(id);
+        let payouts = self.get_tournament_payouts(id)?;
+        let prize_pool = self.tournaments.getter(id).prize_pool.get();
+        let shares = tournament::prize_shares(prize_pool, &payouts, entrants.len());
+        for (rank, (index, amount)) in tournament::rank(&scores).into_iter().zip(shares).enumerate() {
+            let player = entrants[index];
+            let balance = self.prizes.get(player);
+            self.prizes.insert(player, balance + amount);
+            log(self.vm(), PrizeAwarded { id, player, rank: U256::from(rank + 1), amount });
+        }
+        self.tournaments.setter(id).finalized.set(true);
+        log(self.vm(), TournamentFinalized { id, prize_pool });
+        Ok(())
+    }
+
+    pub fn prize_balance(&self, player: Address) -> U256 {
+        self.prizes.get(player)
+    }
+
+    pub fn withdraw_prizes(&mut self) -> Result<(), CounterError> {
+        let player = self.msg_sender();
+        let amount = self.prizes.get(player);
+        self.prizes.insert(player, U256::ZERO);
+        self.vm()
+            .transfer_eth(player, amount)
+            .map_err(|_| CounterError::PrizeTransferFailed(PrizeTransferFailed { player, amount }))?;
+        log(self.vm(), PrizeWithdrawn { player, amount });
+        Ok(())
+    }
+
+    pub fn is_signer(&self, signer: Address) -> bool {
+        self.signers.get(signer)
+    }
+
+    pub fn get_signers(&self) -> Vec<Address> {
+        (0..self.signer_list.len()).map(|index| self.signer_list.get(index).unwrap()).collect()
     }
-}
\ No newline at end of file
+
+    pub fn signer_threshold(&self) -> U256 {
+        self.signer_threshold.get()
+    }
+
+    /// To rotate a key, add its replacement before removing it; claims carrying
+    /// signatures from both keep verifying throughout.
+    pub fn add_signer(&mut self, signer: Address) -> Result<(), CounterError> {
+        self.only_owner()?;
+        if signer == Address::ZERO {
+            return Err(CounterError::InvalidSigner(InvalidSigner { signer }));
+        }
+        if self.signers.get(signer) {
+            return Err(CounterError::SignerAlreadyAdded(SignerAlreadyAdded { signer }));
+        }
+        self.signers.insert(signer, true);
+        self.signer_list.push(signer);
+        log(self.vm(), SignerAdded { signer });
+        Ok(())
+    }
+
+    pub fn remove_signer(&mut self, signer: Address) -> Result<(), CounterError> {
+        self.only_owner()?;
+        if !self.signers.get(signer) {
+            return Err(CounterError::UnknownSigner(UnknownSigner { signer }));
+        }
+        let remaining = self.signer_list.len() - 1;
+        self.check_threshold(self.signer_threshold.get(), remaining)?;
+
+        let index = self.get_signers().iter().position(|&listed| listed == signer).unwrap();
+        let last = self.signer_list.get(remaining).unwrap();
+        self.signer_list.setter(index).unwrap().set(last);
+        self.signer_list.erase_last();
+        self.signers.delete(signer);
+        log(self.vm(), SignerRemoved { signer });
+        Ok(())
+    }
+
+    /// A threshold of 0 turns attestation off, letting players sign their
+    /// own pickups.
+    pub fn set_signer_threshold(&mut self, threshold: U256) -> Result<(), CounterError> {
+        self.only_owner()?;
+        self.check_threshold(threshold, self.signer_list.len())?;
+        self.signer_threshold.set(threshold);
+        log(self.vm(), SignerThresholdSet { threshold });
+        Ok(())
+    }
+
+    pub fn balance_of(&self, player: Address, color: U256) -> U256 {
+        self.balances.get(player).get(color)
+    }
+
+    pub fn get_player_sword_counts(&self, player: Address) -> (U256, U256, U256, U256) {
+        let balances = self.balances.get(player);
+        (
+            balances.get(U256::from(RED)),
+            balances.get(U256::from(GREEN)),
+            balances.get(U256::from(BLUE)),
+            balances.get(U256::from(NORMAL)),
+        )
+    }
+
+    pub fn transfer_swords(&mut self, to: Address, color: U256, amount: U256) -> Result<(), CounterError> {
+        Self::color_index(color)?;
+        let from = self.msg_sender();
+        if to == Address::ZERO || to == from {
+            return Err(CounterError::InvalidRecipient(InvalidRecipient { to }));
+        }
+        let balance = self.balance_of(from, color);
+        if balance < amount {
+            return Err(CounterError::InsufficientSwords(InsufficientSwords {
+                color,
+                balance,
+                requested: amount,
+            }));
+        }
+        self.balances.setter(from).setter(color).set(balance - amount);
+        self.add_swords(to, color, amount);
+        log(self.vm(), SwordsTransferred { from, to, color, amount });
+        Ok(())
+    }
+
+    pub fn gold_token(&self) -> Address {
+        self.gold_token.get()
+    }
+
+    pub fn set_gold_token(&mut self, token: Address) -> Result<(), CounterError> {
+        self.only_owner()?;
+        self.gold_token.set(token);
+        log(self.vm(), GoldTokenSet { token });
+        Ok(())
+    }
+
+    pub fn trusted_forwarder(&self) -> Address {
+        self.trusted_forwarder.get()
+    }
+
+    /// ERC-2771 discovery: relayers check this before forwarding requests.
+    pub fn is_trusted_forwarder(&self, forwarder: Address) -> bool {
+        forwarder != Address::ZERO && forwarder == self.trusted_forwarder.get()
+    }
+
+    /// Setting the zero address turns meta-transactions off.
+    pub fn set_trusted_forwarder(&mut self, forwarder: Address) -> Result<(), CounterError> {
+        self.only_owner()?;
+        self.trusted_forwarder.set(forwarder);
+        log(self.vm(), TrustedForwarderSet { forwarder });
+        Ok(())
+    }
+
+    pub fn get_loot_table(&self) -> (U256, U256, U256, U256) {
+        (
+            self.loot_weights.get(RED).unwrap(),
+            self.loot_weights.get(GREEN).unwrap(),
+            self.loot_weights.get(BLUE).unwrap(),
+            self.loot_weights.get(NORMAL).unwrap(),
+        )
+    }
+
+    pub fn set_loot_weight(&mut self, color: U256, weight: U256) -> Result<(), CounterError> {
+        self.only_owner()?;
+        let index = Self::color_index(color)?;
+        self.loot_weights.setter(index).unwrap().set(weight);
+        log(self.vm(), LootWeightSet { color, weight });
+        Ok(())
+    }
+
+    pub fn get_daily_reward(&self) -> (U256, U256, U256, U256) {
+        (
+            self.daily_reward.get(RED).unwrap(),
+            self.daily_reward.get(GREEN).unwrap(),
+            self.daily_reward.get(BLUE).unwrap(),
+            self.daily_reward.get(NORMAL).unwrap(),
+        )
+    }
+
+    pub fn set_daily_reward(&mut self, color: U256, amount: U256) -> Result<(), CounterError> {
+        self.only_owner()?;
+        let index = Self::color_index(color)?;
+        self.daily_reward.setter(index).unwrap().set(amount);
+        log(self.vm(), DailyRewardSet { color, amount });
+        Ok(())
+    }
+
+    /// Earliest timestamp at which `player` can claim again; 0 if they never have.
+    pub fn next_daily_claim(&self, player: Address) -> U256 {
+        let last = self.last_daily_claims.get(player);
+        if last == U256::ZERO {
+            return U256::ZERO;
+        }
+        last + U256::from(DAILY_COOLDOWN)
+    }
+
+    /// Grants the daily sword bundle. It is a gift rather than a pickup, so it
+    /// neither mints gold nor scores in tournaments.
+    pub fn claim_daily(&mut self) -> Result<(), CounterError> {
+        let player = self.msg_sender();
+        let now = U256::from(self.vm().block_timestamp());
+        let next_claim_time = self.next_daily_claim(player);
+        if now < next_claim_time {
+            let remaining = next_claim_time - now;
+            return Err(CounterError::DailyCooldown(DailyCooldown { next_claim_time, remaining }));
+        }
+
+        self.last_daily_claims.insert(player, now);
+        for color in 0..COLOR_COUNT {
+            let amount = self.daily_reward.get(color).unwrap();
+            if amount > U256::ZERO {
+                self.add_swords(player, U256::from(color), amount);
+            }
+        }
+        let next_claim_time = now + U256::from(DAILY_COOLDOWN);
+        log(self.vm(), DailyClaimed { player, next_claim_time });
+        Ok(())
+    }
+
+    pub fn set_name(&mut self, name: String) -> Result<(), CounterError> {
+        Self::validate_name(&name)?;
+        let player = self.msg_sender();
+        let key = self.name_key(&name);
+        let current_owner = self.name_owners.get(key);
+        if current_owner == player {
+            return Ok(());
+        }
+        if current_owner != Address::ZERO {
+            return Err(CounterError::NameTaken(NameTaken { name, owner: current_owner }));
+        }
+
+        // Release the previous name so another player can claim it.
+        let previous = self.names.get(player).get_string();
+        if !previous.is_empty() {
+            let previous_key = self.name_key(&previous);
+            self.name_owners.delete(previous_key);
+        }
+
+        self.name_owners.insert(key, player);
+        self.names.setter(player).set_str(&name);
+        log(self.vm(), NameSet { player, name });
+        Ok(())
+    }
+
+    pub fn name_of(&self, player: Address) -> String {
+        self.names.get(player).get_string()
+    }
+
+    pub fn address_of(&self, name: String) -> Address {
+        self.name_owners.get(self.name_key(&name))
+    }
+
+    /// Skin 0 is the default art; every player owns it without a grant.
+    pub fn owns_skin(&self, player: Address, skin: U256) -> bool {
+        skin == DEFAULT_SKIN || self.owned_skins.get(player).get(skin)
+    }
+
+    /// Granted skins in grant order, excluding the default skin.
+    pub fn get_owned_skins(&self, player: Address) -> Vec<U256> {
+        let skins = self.skin_lists.get(player);
+        (0..skins.len()).map(|index| skins.get(index).unwrap()).collect()
+    }
+
+    pub fn grant_skin(&mut self, player: Address, skin: U256) -> Result<(), CounterError> {
+        self.only_owner()?;
+        if self.owns_skin(player, skin) {
+            return Err(CounterError::SkinAlreadyOwned(SkinAlreadyOwned { player, skin }));
+        }
+        self.owned_skins.setter(player).insert(skin, true);
+        self.skin_lists.setter(player).push(skin);
+        log(self.vm(), SkinGranted { player, skin });
+        Ok(())
+    }
+
+    pub fn equipped_skin(&self, player: Address) -> U256 {
+        self.equipped_skins.get(player)
+    }
+
+    pub fn equip_skin(&mut self, skin: U256) -> Result<(), CounterError> {
+        let player = self.msg_sender();
+        if !self.owns_skin(player, skin) {
+            return Err(CounterError::SkinNotOwned(SkinNotOwned { player, skin }));
+        }
+        self.equipped_skins.insert(player, skin);
+        log(self.vm(), SkinEquipped { player, skin });
+        Ok(())
+    }
+
+    /// Token ids pack the sword as `tier * COLOR_COUNT + color`.
+    pub fn uri(&self, id: U256) -> Result<String, CounterError> {
+        if id >= U256::from(COLOR_COUNT * metadata::TIER_COUNT) {
+            return Err(CounterError::InvalidTokenId(InvalidTokenId { id }));
+        }
+        let id = id.to::<usize>();
+        Ok(metadata::token_uri(id % COLOR_COUNT, id / COLOR_COUNT))
+    }
+}
+
+impl Counter {
+    /// The caller, or the player a trusted forwarder relayed the call for.
+    fn msg_sender(&self) -> Address {
+        forwarder::forwarded_sender().unwrap_or_else(|| self.vm().msg_sender())
+    }
+
+    fn record_pickup(&mut self, player: Address, color: U256, amount: U256) -> Result<(), CounterError> {
+        self.score_tournament_pickup(player, amount);
+        self.credit_pickup(player, color, amount)
+    }
+
+    /// Everything a pickup earns except tournament score.
+    fn credit_pickup(&mut self, player: Address, color: U256, amount: U256) -> Result<(), CounterError> {
+        let total = match color.to::<u8>() {
+            RED => &mut self.red_swords,
+            GREEN => &mut self.green_swords,
+            BLUE => &mut self.blue_swords,
+            _ => &mut self.normal_swords,
+        };
+        total.set(total.get() + amount);
+        self.add_swords(player, color, amount);
+        let pickups = self.pickups.get(player);
+        self.pickups.insert(player, pickups + amount);
+        let guild = self.guild_of.get(player);
+        if guild != U256::ZERO {
+            let mut guild = self.guilds.setter(guild);
+            let total = guild.total_swords.get();
+            guild.total_swords.set(total + amount);
+        }
+        log(self.vm(), SwordFound { player, color, amount });
+        self.reward_gold(player, GOLD_PER_PICKUP * amount)
+    }
+
+    /// Counts pickups made while the player's tournament is running.
+    fn score_tournament_pickup(&mut self, player: Address, amount: U256) {
+        let id = self.current_tournament.get(player);
+        if id == U256::ZERO {
+            return;
+        }
+        let now = U256::from(self.vm().block_timestamp());
+        let mut running = self.tournaments.setter(id);
+        if now >= running.start_time.get() && now < running.end_time.get() {
+            let score = running.scores.get(player);
+            running.scores.insert(player, score + amount);
+        }
+    }
+
+    fn guild_exists(&self, id: U256) -> Result<(), CounterError> {
+        if id == U256::ZERO || id > self.guild_count.get() {
+            return Err(CounterError::UnknownGuild(UnknownGuild { id }));
+        }
+        Ok(())
+    }
+
+    fn not_in_guild(&self, player: Address) -> Result<(), CounterError> {
+        let id = self.guild_of.get(player);
+        if id != U256::ZERO {
+            return Err(CounterError::AlreadyInGuild(AlreadyInGuild { id }));
+        }
+        Ok(())
+    }
+
+    fn add_guild_member(&mut self, id: U256, player: Address) {
+        self.guilds.setter(id).members.push(player);
+        self.guild_of.insert(player, id);
+        log(self.vm(), GuildJoined { id, player });
+    }
+
+    fn add_kills(&mut self, player: Address, count: U256) -> Result<(), CounterError> {
+        let kills = self.kills.get(player);
+        self.kills.insert(player, kills + count);
+        self.total_kills.set(self.total_kills.get() + count);
+        log(self.vm(), KillsRecorded { player, count });
+        self.reward_gold(player, GOLD_PER_KILL * count)
+    }
+
+    /// Kills divided by swords, treating no swords as one so the ratio stays defined.
+    fn kill_ratio(kills: U256, swords: U256) -> U256 {
+        kills * U256::from(10).pow(U256::from(KILL_RATIO_DECIMALS)) / swords.max(U256::from(1))
+    }
+
+    /// Rejects expired claims and claims that do not use the player's next nonce.
+    fn check_claim(&self, player: Address, nonce: U256, deadline: U256) -> Result<(), CounterError> {
+        let timestamp = U256::from(self.vm().block_timestamp());
+        if timestamp > deadline {
+            return Err(CounterError::ClaimExpired(ClaimExpired { deadline, timestamp }));
+        }
+        let expected = self.nonces.get(player);
+        if nonce != expected {
+            return Err(CounterError::InvalidNonce(InvalidNonce { expected, provided: nonce }));
+        }
+        Ok(())
+    }
+
+    /// Counts signatures over `digest` from distinct registered signers.
+    /// Signatures from unknown or removed keys are skipped rather than
+    /// rejected, so claims signed before a rotation still carry enough.
+    fn check_attestation(&self, player: Address, digest: B256, signatures: &[u8]) -> Result<(), CounterError> {
+        if signatures.is_empty() || signatures.len() % 65 != 0 {
+            return Err(CounterError::InvalidSignature(InvalidSignature {}));
+        }
+        let threshold = self.signer_threshold.get();
+        if threshold == U256::ZERO {
+            if signatures.len() != 65 || signatures::recover(self.vm(), digest, signatures) != Some(player) {
+                return Err(CounterError::InvalidSignature(InvalidSignature {}));
+            }
+            return Ok(());
+        }
+
+        let mut attesters: Vec<Address> = Vec::new();
+        for signature in signatures.chunks(65) {
+            if let Some(signer) = signatures::recover(self.vm(), digest, signature) {
+                if self.signers.get(signer) && !attesters.contains(&signer) {
+                    attesters.push(signer);
+                }
+            }
+        }
+        let valid = U256::from(attesters.len());
+        if valid < threshold {
+            return Err(CounterError::InsufficientSignatures(InsufficientSignatures { required: threshold, valid }));
+        }
+        Ok(())
+    }
+
+    /// Rejects calls that skip attestation once the owner requires it.
+    fn check_unattested(&self) -> Result<(), CounterError> {
+        let threshold = self.signer_threshold.get();
+        if threshold != U256::ZERO {
+            return Err(CounterError::InsufficientSignatures(InsufficientSignatures { required: threshold, valid: U256::ZERO }));
+        }
+        Ok(())
+    }
+
+    fn check_threshold(&self, threshold: U256, signers: usize) -> Result<(), CounterError> {
+        if threshold > U256::from(signers) {
+            let signers = U256::from(signers);
+            return Err(CounterError::InvalidThreshold(InvalidThreshold { threshold, signers }));
+        }
+        Ok(())
+    }
+
+    fn tournament_exists(&self, id: U256) -> Result<(), CounterError> {
+        if id == U256::ZERO || id > self.tournament_count.get() {
+            return Err(CounterError::UnknownTournament(UnknownTournament { id }));
+        }
+        Ok(())
+    }
+
+    fn tournament_entries(&self, id: U256) -> (Vec<Address>, Vec<U256>) {
+        let tournament = self.tournaments.getter(id);
+        (0..tournament.entrants.len())
+            .map(|index| {
+                let player = tournament.entrants.get(index).unwrap();
+                (player, tournament.scores.get(player))
+            })
+            .unzip()
+    }
+
+    fn add_swords(&mut self, player: Address, color: U256, amount: U256) {
+        let mut balances = self.balances.setter(player);
+        let mut balance = balances.setter(color);
+        let current = balance.get();
+        balance.set(current + amount);
+    }
+
+    /// Mints gold through the token contract. Rewards are skipped until the
+    /// owner configures a token, so the sword contract also works standalone.
+    fn reward_gold(&mut self, player: Address, amount: U256) -> Result<(), CounterError> {
+        let token = self.gold_token.get();
+        if token == Address::ZERO {
+            return Ok(());
+        }
+        IGoldToken::new(token)
+            .mint(&mut *self, player, amount)
+            .map_err(|_| CounterError::GoldMintFailed(GoldMintFailed { token }))
+    }
+
+    fn only_owner(&self) -> Result<(), CounterError> {
+        let caller = self.msg_sender();
+        if caller != self.owner.get() {
+            return Err(CounterError::NotOwner(NotOwner { caller }));
+        }
+        Ok(())
+    }
+
+    /// Names are 3 to 16 characters of lowercase ASCII letters, digits and
+    /// underscores, so uniqueness never depends on case or Unicode folding.
+    fn validate_name(name: &str) -> Result<(), CounterError> {
+        if name.len() < NAME_MIN_LENGTH || name.len() > NAME_MAX_LENGTH {
+            let length = U256::from(name.len());
+            return Err(CounterError::InvalidNameLength(InvalidNameLength { length }));
+        }
+        for (index, byte) in name.bytes().enumerate() {
+            if !(byte.is_ascii_lowercase() || byte.is_ascii_digit() || byte == b'_') {
+                let index = U256::from(index);
+                return Err(CounterError::InvalidNameCharacter(InvalidNameCharacter { index }));
+            }
+        }
+        Ok(())
+    }
+
+    fn validate_guild_tag(tag: &str) -> Result<(), CounterError> {
+        let valid_length = (GUILD_TAG_MIN_LENGTH..=GUILD_TAG_MAX_LENGTH).contains(&tag.len());
+        if !valid_length || !tag.bytes().all(|byte| byte.is_ascii_uppercase() || byte.is_ascii_digit()) {
+            return Err(CounterError::InvalidGuildTag(InvalidGuildTag { tag: tag.into() }));
+        }
+        Ok(())
+    }
+
+    fn name_key(&self, name: &str) -> B256 {
+        self.vm().native_keccak256(name.as_bytes())
+    }
+
+    fn color_index(color: U256) -> Result<usize, CounterError> {
+        if color >= U256::from(COLOR_COUNT) {
+            return Err(CounterError::InvalidColor(InvalidColor { color }));
+        }
+        Ok(color.to::<usize>())
+    }
+}
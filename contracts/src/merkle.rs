@@ -0,0 +1,30 @@
+//! Merkle proofs for batch settlement, matching the `sword-merkle` crate the
+//! game server builds trees with.
+
+use alloy_sol_types::SolValue;
+use stylus_sdk::{
+    alloy_primitives::{Address, B256, U256},
+    prelude::*,
+};
+
+use crate::COLOR_COUNT;
+
+/// `keccak256(keccak256(abi.encode(player, colorCounts)))`. The double hash
+/// keeps a leaf from being passed off as an inner node.
+pub fn session_leaf(vm: &dyn Host, player: Address, color_counts: [U256; COLOR_COUNT]) -> B256 {
+    let encoded = (player, color_counts).abi_encode();
+    let inner = vm.native_keccak256(&encoded);
+    vm.native_keccak256(inner.as_slice())
+}
+
+/// Inner nodes hash their children in sorted order.
+pub fn verify(vm: &dyn Host, proof: &[B256], root: B256, leaf: B256) -> bool {
+    let computed = proof.iter().fold(leaf, |node, sibling| {
+        let (low, high) = if node <= *sibling { (node, *sibling) } else { (*sibling, node) };
+        let mut preimage = [0u8; 64];
+        preimage[..32].copy_from_slice(low.as_slice());
+        preimage[32..].copy_from_slice(high.as_slice());
+        vm.native_keccak256(&preimage)
+    });
+    computed == root
+}
@@ -0,0 +1,134 @@
+//! Fully on-chain token metadata: a JSON document embedding an SVG, both
+//! delivered as base64 data URIs so wallets need no external host.
+
+use alloc::string::String;
+
+use crate::COLOR_COUNT;
+
+pub const TIER_COUNT: usize = 4;
+
+const COLOR_NAMES: [&str; COLOR_COUNT] = ["Red", "Green", "Blue", "Normal"];
+const BLADE_FILLS: [&str; COLOR_COUNT] = ["#e53935", "#43a047", "#1e88e5", "#b0bec5"];
+const TIER_NAMES: [&str; TIER_COUNT] = ["Common", "Rare", "Epic", "Legendary"];
+const TIER_BACKGROUNDS: [&str; TIER_COUNT] = ["#263238", "#1a237e", "#4a148c", "#e65100"];
+
+const BASE64_ALPHABET: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// Returns the `data:application/json;base64,...` URI for a sword. Callers
+/// must pass a color below `COLOR_COUNT` and a tier below `TIER_COUNT`.
+pub fn token_uri(color: usize, tier: usize) -> String {
+    let json = token_json(color, tier);
+    format!("data:application/json;base64,{}", base64_encode(json.as_bytes()))
+}
+
+fn token_json(color: usize, tier: usize) -> String {
+    let svg = sword_svg(color, tier);
+    format!(
+        concat!(
+            "{{\"name\":\"{tier} {color} Sword\",",
+            "\"description\":\"A {tier_lower} {color_lower} sword collected in Bevy Stylus.\",",
+            "\"image\":\"data:image/svg+xml;base64,{image}\",",
+            "\"attributes\":[",
+            "{{\"trait_type\":\"Color\",\"value\":\"{color}\"}},",
+            "{{\"trait_type\":\"Tier\",\"value\":\"{tier}\"}}",
+            "]}}"
+        ),
+        tier = TIER_NAMES[tier],
+        color = COLOR_NAMES[color],
+        tier_lower = TIER_NAMES[tier].to_lowercase(),
+        color_lower = COLOR_NAMES[color].to_lowercase(),
+        image = base64_encode(svg.as_bytes()),
+    )
+}
+
+/// Single quotes keep the SVG free of characters that would need JSON escaping.
+fn sword_svg(color: usize, tier: usize) -> String {
+    let mut stars = String::new();
+    for star in 0..=tier {
+        stars.push_str(&format!(
+            "<circle cx='{}' cy='56' r='2' fill='#ffd54f'/>",
+            26 + star * 4
+        ));
+    }
+    format!(
+        concat!(
+            "<svg xmlns='http://www.w3.org/2000/svg' viewBox='0 0 64 64'>",
+            "<rect width='64' height='64' fill='{background}'/>",
+            "<path d='M32 6 L37 38 L27 38 Z' fill='{blade}'/>",
+            "<rect x='22' y='38' width='20' height='4' fill='#795548'/>",
+            "<rect x='30' y='42' width='4' height='8' fill='#5d4037'/>",
+            "{stars}",
+            "</svg>"
+        ),
+        background = TIER_BACKGROUNDS[tier],
+        blade = BLADE_FILLS[color],
+        stars = stars,
+    )
+}
+
+fn base64_encode(input: &[u8]) -> String {
+    let mut output = String::with_capacity(input.len().div_ceil(3) * 4);
+    for chunk in input.chunks(3) {
+        let bytes = [chunk[0], *chunk.get(1).unwrap_or(&0), *chunk.get(2).unwrap_or(&0)];
+        let triple = (bytes[0] as u32) << 16 | (bytes[1] as u32) << 8 | bytes[2] as u32;
+        for position in 0..4 {
+            if position <= chunk.len() {
+                let index = (triple >> (18 - position * 6)) & 0x3f;
+                output.push(BASE64_ALPHABET[index as usize] as char);
+            } else {
+                output.push('=');
+            }
+        }
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use base64::{engine::general_purpose::STANDARD, Engine};
+    use serde_json::Value;
+
+    fn decode_data_uri(uri: &str, prefix: &str) -> Vec<u8> {
+        let payload = uri.strip_prefix(prefix).expect("unexpected data URI prefix");
+        STANDARD.decode(payload).expect("payload is not valid base64")
+    }
+
+    #[test]
+    fn base64_matches_reference_encoder() {
+        for input in [&b""[..], b"f", b"fo", b"foo", b"foob", b"fooba", b"foobar"] {
+            assert_eq!(base64_encode(input), STANDARD.encode(input));
+        }
+    }
+
+    #[test]
+    fn token_uri_decodes_to_valid_metadata() {
+        for (color, color_name) in COLOR_NAMES.iter().enumerate() {
+            for (tier, tier_name) in TIER_NAMES.iter().enumerate() {
+                let uri = token_uri(color, tier);
+                let json = decode_data_uri(&uri, "data:application/json;base64,");
+                let metadata: Value = serde_json::from_slice(&json).expect("metadata is not valid JSON");
+
+                let expected_name = format!("{} {} Sword", tier_name, color_name);
+                assert_eq!(metadata["name"], expected_name.as_str());
+                assert!(metadata["description"].is_string());
+
+                let attributes = metadata["attributes"].as_array().unwrap();
+                assert_eq!(attributes.len(), 2);
+                assert_eq!(attributes[0]["trait_type"], "Color");
+                assert_eq!(attributes[0]["value"], *color_name);
+                assert_eq!(attributes[1]["trait_type"], "Tier");
+                assert_eq!(attributes[1]["value"], *tier_name);
+
+                let image = metadata["image"].as_str().unwrap();
+                let svg = decode_data_uri(image, "data:image/svg+xml;base64,");
+                let svg = String::from_utf8(svg).unwrap();
+                assert!(svg.starts_with("<svg xmlns='http://www.w3.org/2000/svg'"));
+                assert!(svg.ends_with("</svg>"));
+                assert!(svg.contains(BLADE_FILLS[color]));
+                assert_eq!(svg.matches("<circle").count(), tier + 1);
+            }
+        }
+    }
+}
@@ -0,0 +1,101 @@
+//! EIP-712 hashing and `ecrecover` for signed pickup and kill claims.
+
+use alloy_sol_types::SolValue;
+use stylus_sdk::{
+    alloy_primitives::{address, b256, Address, B256, U256},
+    keccak_const::Keccak256,
+    prelude::*,
+    stylus_core::calls::context::Call,
+};
+
+const ECRECOVER: Address = address!("0000000000000000000000000000000000000001");
+
+/// Signatures with `s` in the upper half of the curve order are rejected so a
+/// valid signature cannot be replayed in its malleated form.
+const SECP256K1_HALF_ORDER: B256 =
+    b256!("7fffffffffffffffffffffffffffffff5d576e7357a4501ddfe92f46681b20a0");
+
+pub const DOMAIN_NAME: &str = "BevyStylusSwords";
+pub const DOMAIN_VERSION: &str = "1";
+
+const DOMAIN_TYPEHASH: [u8; 32] = Keccak256::new()
+    .update(b"EIP712Domain(string name,string version,uint256 chainId,address verifyingContract)")
+    .finalize();
+
+const PICKUP_TYPEHASH: [u8; 32] = Keccak256::new()
+    .update(b"Pickup(address player,uint256 color,uint256 amount,uint256 nonce,uint256 deadline)")
+    .finalize();
+
+const KILLS_TYPEHASH: [u8; 32] = Keccak256::new()
+    .update(b"Kills(address player,uint256 count,uint256 nonce,uint256 deadline)")
+    .finalize();
+
+const DOMAIN_NAME_HASH: [u8; 32] = Keccak256::new().update(DOMAIN_NAME.as_bytes()).finalize();
+const DOMAIN_VERSION_HASH: [u8; 32] = Keccak256::new().update(DOMAIN_VERSION.as_bytes()).finalize();
+
+pub fn domain_separator(vm: &dyn Host, chain_id: u64, verifying_contract: Address) -> B256 {
+    let encoded = (
+        B256::from(DOMAIN_TYPEHASH),
+        B256::from(DOMAIN_NAME_HASH),
+        B256::from(DOMAIN_VERSION_HASH),
+        U256::from(chain_id),
+        verifying_contract,
+    )
+        .abi_encode();
+    vm.native_keccak256(&encoded)
+}
+
+pub fn pickup_struct_hash(
+    vm: &dyn Host,
+    player: Address,
+    color: U256,
+    amount: U256,
+    nonce: U256,
+    deadline: U256,
+) -> B256 {
+    let encoded = (B256::from(PICKUP_TYPEHASH), player, color, amount, nonce, deadline).abi_encode();
+    vm.native_keccak256(&encoded)
+}
+
+pub fn kills_struct_hash(vm: &dyn Host, player: Address, count: U256, nonce: U256, deadline: U256) -> B256 {
+    let encoded = (B256::from(KILLS_TYPEHASH), player, count, nonce, deadline).abi_encode();
+    vm.native_keccak256(&encoded)
+}
+
+pub fn typed_data_digest(vm: &dyn Host, domain_separator: B256, struct_hash: B256) -> B256 {
+    let mut preimage = [0u8; 66];
+    preimage[..2].copy_from_slice(&[0x19, 0x01]);
+    preimage[2..34].copy_from_slice(domain_separator.as_slice());
+    preimage[34..].copy_from_slice(struct_hash.as_slice());
+    vm.native_keccak256(&preimage)
+}
+
+/// Recovers the signer of `digest` from a 65-byte `r || s || v` signature,
+/// returning `None` for malformed or malleable signatures.
+pub fn recover(vm: &dyn Host, digest: B256, signature: &[u8]) -> Option<Address> {
+    if signature.len() != 65 {
+        return None;
+    }
+    let (r, s, v) = (&signature[..32], &signature[32..64], signature[64]);
+    if s > SECP256K1_HALF_ORDER.as_slice() {
+        return None;
+    }
+    let v = match v {
+        0 | 1 => v + 27,
+        27 | 28 => v,
+        _ => return None,
+    };
+
+    let mut input = [0u8; 128];
+    input[..32].copy_from_slice(digest.as_slice());
+    input[63] = v;
+    input[64..96].copy_from_slice(r);
+    input[96..].copy_from_slice(s);
+
+    let output = vm.static_call(&Call::new(), ECRECOVER, &input).ok()?;
+    if output.len() != 32 {
+        return None;
+    }
+    let signer = Address::from_slice(&output[12..]);
+    (signer != Address::ZERO).then_some(signer)
+}
@@ -0,0 +1,55 @@
+//! Ranking and prize math for tournaments, kept free of storage so it can be
+//! checked natively.
+
+use alloc::vec::Vec;
+
+use stylus_sdk::alloy_primitives::U256;
+
+/// Bounds the work `finalizeTournament` does when ranking entrants.
+pub const MAX_ENTRANTS: usize = 100;
+/// Bounds how many places a tournament can pay out.
+pub const MAX_PAID_PLACES: usize = 10;
+
+/// Entrant indices from best to worst score. Ties go to whoever joined first.
+pub fn rank(scores: &[U256]) -> Vec<usize> {
+    let mut order: Vec<usize> = (0..scores.len()).collect();
+    order.sort_by(|&a, &b| scores[b].cmp(&scores[a]));
+    order
+}
+
+/// Splits `pool` by whole `percentages` over `finishers` ranked entrants.
+/// Places nobody finished in, and rounding dust, go to the winner.
+pub fn prize_shares(pool: U256, percentages: &[U256], finishers: usize) -> Vec<U256> {
+    let mut shares: Vec<U256> = percentages
+        .iter()
+        .take(finishers)
+        .map(|percentage| pool * percentage / U256::from(100))
+        .collect();
+    if !shares.is_empty() {
+        let paid = shares.iter().skip(1).fold(U256::ZERO, |sum, share| sum + share);
+        shares[0] = pool - paid;
+    }
+    shares
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn u(values: &[u64]) -> Vec<U256> {
+        values.iter().map(|&value| U256::from(value)).collect()
+    }
+
+    #[test]
+    fn ties_keep_join_order() {
+        assert_eq!(rank(&u(&[3, 7, 3, 9])), vec![3, 1, 0, 2]);
+    }
+
+    #[test]
+    fn unfilled_places_and_dust_go_to_the_winner() {
+        assert_eq!(prize_shares(U256::from(1000), &u(&[50, 30, 20]), 3), u(&[500, 300, 200]));
+        assert_eq!(prize_shares(U256::from(1000), &u(&[50, 30, 20]), 2), u(&[700, 300]));
+        assert_eq!(prize_shares(U256::from(10), &u(&[34, 33, 33]), 3), u(&[4, 3, 3]));
+        assert!(prize_shares(U256::from(10), &u(&[100]), 0).is_empty());
+    }
+}
@@ -0,0 +1,92 @@
+//! Settles sessions with trees built by the `sword-merkle` crate, as the game
+//! server does, and claims them through the contract.
+
+mod common;
+
+use stylus_hello_world::{Counter, CounterError, RED};
+use stylus_sdk::{
+    alloy_primitives::{Address, B256, U256},
+    testing::*,
+};
+use sword_merkle::{Claim, SessionResult, Settlement};
+
+const SERVER: Address = Address::repeat_byte(0x5e);
+const ALICE: Address = Address::repeat_byte(0x01);
+const BOB: Address = Address::repeat_byte(0x02);
+
+fn deploy() -> (TestVM, Counter) {
+    let (vm, mut swords) = common::deploy();
+    swords.set_settlement_server(SERVER).unwrap();
+    (vm, swords)
+}
+
+fn settle(vm: &TestVM, swords: &mut Counter, results: &[(Address, [u64; 4])]) -> Settlement {
+    let settlement = Settlement::build(
+        results
+            .iter()
+            .map(|(player, color_counts)| SessionResult { player: player.0 .0, color_counts: *color_counts })
+            .collect(),
+    );
+    vm.set_sender(SERVER);
+    swords.post_root(B256::from(settlement.root)).unwrap();
+    settlement
+}
+
+fn claim(vm: &TestVM, swords: &mut Counter, player: Address, claim: &Claim) -> Result<(), CounterError> {
+    vm.set_sender(player);
+    let proof = claim.proof.iter().copied().map(B256::from).collect();
+    swords.claim(proof, claim.result.color_counts.map(U256::from))
+}
+
+#[test]
+fn only_the_settlement_server_posts_roots() {
+    let (vm, mut swords) = deploy();
+
+    vm.set_sender(ALICE);
+    assert!(matches!(swords.post_root(B256::repeat_byte(1)), Err(CounterError::NotSettlementServer(_))));
+    vm.set_sender(SERVER);
+    swords.post_root(B256::repeat_byte(1)).unwrap();
+    assert_eq!(swords.settlement_root(), B256::repeat_byte(1));
+    assert_eq!(swords.settlement_epoch(), U256::from(1));
+}
+
+#[test]
+fn players_claim_each_sword_once_across_roots() {
+    let (vm, mut swords) = deploy();
+
+    let first = settle(&vm, &mut swords, &[(ALICE, [2, 0, 1, 0]), (BOB, [0, 3, 0, 0])]);
+    let alice = first.claim_for(&ALICE.0 .0).unwrap().clone();
+    claim(&vm, &mut swords, ALICE, &alice).unwrap();
+    assert_eq!(swords.balance_of(ALICE, U256::from(RED)), U256::from(2));
+    assert!(matches!(claim(&vm, &mut swords, ALICE, &alice), Err(CounterError::NothingToClaim(_))));
+
+    // Bob's leaf only proves Bob's counts.
+    let bob = first.claim_for(&BOB.0 .0).unwrap().clone();
+    assert!(matches!(claim(&vm, &mut swords, ALICE, &bob), Err(CounterError::InvalidProof(_))));
+
+    // The next root carries Alice's running totals; only the new swords are credited.
+    let second = settle(&vm, &mut swords, &[(ALICE, [2, 0, 4, 0]), (BOB, [0, 3, 0, 0])]);
+    claim(&vm, &mut swords, ALICE, second.claim_for(&ALICE.0 .0).unwrap()).unwrap();
+    assert_eq!(swords.get_player_sword_counts(ALICE), (U256::from(2), U256::ZERO, U256::from(4), U256::ZERO));
+    assert_eq!(swords.get_settled_sword_counts(ALICE).2, U256::from(4));
+    assert_eq!(swords.get_sword_counts().2, U256::from(4));
+}
+
+#[test]
+fn settled_swords_do_not_score_in_a_running_tournament() {
+    let (vm, mut swords) = deploy();
+    let start = 1_700_000_000;
+    vm.set_block_timestamp(start);
+    let payouts = vec![U256::from(100)];
+    swords.create_tournament(U256::ZERO, U256::from(start), U256::from(start + 3600), payouts).unwrap();
+    vm.set_sender(ALICE);
+    swords.join_tournament(U256::from(1)).unwrap();
+
+    let settlement = settle(&vm, &mut swords, &[(ALICE, [3, 0, 0, 0])]);
+    claim(&vm, &mut swords, ALICE, settlement.claim_for(&ALICE.0 .0).unwrap()).unwrap();
+    assert_eq!(swords.balance_of(ALICE, U256::from(RED)), U256::from(3));
+    assert_eq!(swords.tournament_score(U256::from(1), ALICE), U256::ZERO);
+
+    swords.increment_sword(U256::from(RED)).unwrap();
+    assert_eq!(swords.tournament_score(U256::from(1), ALICE), U256::from(1));
+}
@@ -0,0 +1,120 @@
+//! The sword contract fixture shared by every test binary, plus native
+//! stand-ins for the Stylus host functions used by `sol_interface!` calls,
+//! which bypass `TestVM`. Tests route outgoing calls by setting
+//! [`CALL_HANDLER`]; any test binary reaching a cross-contract call site
+//! must include this module so it links.
+
+// Each test binary uses its own subset of the fixture.
+#![allow(dead_code)]
+
+use std::cell::RefCell;
+
+use stylus_hello_world::Counter;
+use stylus_sdk::{alloy_primitives::Address, testing::TestVM};
+
+pub const OWNER: Address = Address::repeat_byte(0x0a);
+pub const PLAYER: Address = Address::repeat_byte(0x01);
+
+/// Deploys the sword contract from [`OWNER`], who stays the sender.
+pub fn deploy() -> (TestVM, Counter) {
+    let vm = TestVM::default();
+    vm.set_tx_origin(OWNER);
+    vm.set_sender(OWNER);
+    let mut swords = Counter::from(&vm);
+    swords.constructor();
+    (vm, swords)
+}
+
+pub type CallHandler = Box<dyn FnMut(Address, &[u8]) -> Result<Vec<u8>, Vec<u8>>>;
+
+thread_local! {
+    /// Handles every `call_contract` made on this thread with `(to, calldata)`.
+    pub static CALL_HANDLER: RefCell<Option<CallHandler>> = const { RefCell::new(None) };
+    static RETURN_DATA: RefCell<Vec<u8>> = const { RefCell::new(Vec::new()) };
+}
+
+/// # Safety
+/// Called by the Stylus SDK with pointers to a 20-byte address, `calldata_len`
+/// bytes of calldata and a writable length.
+#[no_mangle]
+pub unsafe extern "C" fn call_contract(
+    contract: *const u8,
+    calldata: *const u8,
+    calldata_len: usize,
+    _value: *const u8,
+    _gas: u64,
+    return_data_len: *mut usize,
+) -> u8 {
+    let contract = Address::from_slice(std::slice::from_raw_parts(contract, 20));
+    let calldata = std::slice::from_raw_parts(calldata, calldata_len);
+    let result = CALL_HANDLER.with(|slot| {
+        let mut slot = slot.borrow_mut();
+        let handler = slot.as_mut().expect("unexpected cross-contract call");
+        handler(contract, calldata)
+    });
+    let (status, output) = match result {
+        Ok(output) => (0, output),
+        Err(output) => (1, output),
+    };
+    *return_data_len = output.len();
+    RETURN_DATA.with(|data| *data.borrow_mut() = output);
+    status
+}
+
+/// Fails a call no test expects, with `reason` as its revert data so the
+/// error the contract surfaces says what happened.
+///
+/// # Safety
+/// `return_data_len` must be writable.
+unsafe fn refuse(reason: &str, return_data_len: *mut usize) -> u8 {
+    *return_data_len = reason.len();
+    RETURN_DATA.with(|data| *data.borrow_mut() = reason.as_bytes().to_vec());
+    1
+}
+
+/// # Safety
+/// `dest` must be valid for `size` bytes.
+#[no_mangle]
+pub unsafe extern "C" fn read_return_data(dest: *mut u8, offset: usize, size: usize) -> usize {
+    RETURN_DATA.with(|data| {
+        let data = data.borrow();
+        let available = &data[offset.min(data.len())..];
+        let written = available.len().min(size);
+        std::ptr::copy_nonoverlapping(available.as_ptr(), dest, written);
+        written
+    })
+}
+
+#[no_mangle]
+pub extern "C" fn return_data_size() -> usize {
+    RETURN_DATA.with(|data| data.borrow().len())
+}
+
+#[no_mangle]
+pub extern "C" fn storage_flush_cache(_clear: bool) {}
+
+/// # Safety
+/// `return_data_len` must be writable.
+#[no_mangle]
+pub unsafe extern "C" fn delegate_call_contract(
+    _contract: *const u8,
+    _calldata: *const u8,
+    _calldata_len: usize,
+    _gas: u64,
+    return_data_len: *mut usize,
+) -> u8 {
+    refuse("unexpected delegate call: the sword contract makes none", return_data_len)
+}
+
+/// # Safety
+/// `return_data_len` must be writable.
+#[no_mangle]
+pub unsafe extern "C" fn static_call_contract(
+    _contract: *const u8,
+    _calldata: *const u8,
+    _calldata_len: usize,
+    _gas: u64,
+    return_data_len: *mut usize,
+) -> u8 {
+    refuse("unexpected raw static call: the sword contract makes none", return_data_len)
+}
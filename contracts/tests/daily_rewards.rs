@@ -0,0 +1,56 @@
+mod common;
+
+use common::{OWNER, PLAYER};
+use stylus_hello_world::{Counter, CounterError, BLUE, DAILY_COOLDOWN, NORMAL, RED};
+use stylus_sdk::{alloy_primitives::U256, testing::*};
+
+const NOW: u64 = 1_700_000_000;
+
+fn deploy() -> (TestVM, Counter) {
+    let (vm, swords) = common::deploy();
+    vm.set_block_timestamp(NOW);
+    vm.set_sender(PLAYER);
+    (vm, swords)
+}
+
+#[test]
+fn daily_reward_is_claimable_once_per_cooldown() {
+    let (vm, mut swords) = deploy();
+    assert_eq!(swords.next_daily_claim(PLAYER), U256::ZERO);
+
+    swords.claim_daily().unwrap();
+    assert_eq!(swords.get_player_sword_counts(PLAYER), (U256::from(1), U256::from(1), U256::from(1), U256::ZERO));
+    let next = U256::from(NOW + DAILY_COOLDOWN);
+    assert_eq!(swords.next_daily_claim(PLAYER), next);
+
+    vm.set_block_timestamp(NOW + DAILY_COOLDOWN - 90);
+    match swords.claim_daily() {
+        Err(CounterError::DailyCooldown(error)) => {
+            assert_eq!(error.next_claim_time, next);
+            assert_eq!(error.remaining, U256::from(90));
+        }
+        other => panic!("expected a cooldown error, got {:?}", other),
+    }
+
+    vm.set_block_timestamp(NOW + DAILY_COOLDOWN);
+    swords.claim_daily().unwrap();
+    assert_eq!(swords.balance_of(PLAYER, U256::from(RED)), U256::from(2));
+}
+
+#[test]
+fn owner_configures_the_daily_bundle() {
+    let (vm, mut swords) = deploy();
+    assert!(matches!(swords.set_daily_reward(U256::from(NORMAL), U256::from(5)), Err(CounterError::NotOwner(_))));
+
+    vm.set_sender(OWNER);
+    swords.set_daily_reward(U256::from(NORMAL), U256::from(5)).unwrap();
+    swords.set_daily_reward(U256::from(BLUE), U256::ZERO).unwrap();
+    assert!(matches!(swords.set_daily_reward(U256::from(4), U256::from(1)), Err(CounterError::InvalidColor(_))));
+    assert_eq!(swords.get_daily_reward(), (U256::from(1), U256::from(1), U256::ZERO, U256::from(5)));
+
+    vm.set_sender(PLAYER);
+    swords.claim_daily().unwrap();
+    assert_eq!(swords.get_player_sword_counts(PLAYER), (U256::from(1), U256::from(1), U256::ZERO, U256::from(5)));
+    // The bundle is not a pickup, so world totals are unchanged.
+    assert_eq!(swords.get_sword_counts(), (U256::ZERO, U256::ZERO, U256::ZERO, U256::ZERO));
+}
@@ -0,0 +1,174 @@
+//! Exercises the sword and gold contracts together: every `mint` the sword
+//! contract sends is forwarded into an in-memory gold token, with the sword
+//! contract as `msg.sender`.
+
+mod common;
+
+use std::cell::RefCell;
+
+use alloy_sol_types::{sol, SolCall};
+use common::{OWNER, PLAYER};
+use ethers::{
+    abi::{encode, Token},
+    signers::{LocalWallet, Signer},
+    types::{
+        transaction::eip712::{EIP712Domain, Eip712},
+        Signature, H160, U256 as EthU256,
+    },
+    utils::keccak256,
+};
+use gold_token::{GoldError, GoldToken};
+use stylus_hello_world::{Counter, CounterError, GOLD_PER_KILL, GOLD_PER_PICKUP, BLUE, RED};
+use stylus_sdk::{
+    alloy_primitives::{address, Address, U256},
+    testing::*,
+};
+
+sol! {
+    function mint(address to, uint256 amount);
+}
+
+const SWORDS: Address = Address::repeat_byte(0x5a);
+const GOLD: Address = Address::repeat_byte(0x60);
+const ECRECOVER: Address = address!("0000000000000000000000000000000000000001");
+const CHAIN_ID: u64 = 412346;
+const NOW: u64 = 1_700_000_000;
+
+struct Kills {
+    player: H160,
+    count: u64,
+    nonce: u64,
+    deadline: u64,
+}
+
+impl Eip712 for Kills {
+    type Error = std::convert::Infallible;
+
+    fn domain(&self) -> Result<EIP712Domain, Self::Error> {
+        Ok(EIP712Domain {
+            name: Some("BevyStylusSwords".to_string()),
+            version: Some("1".to_string()),
+            chain_id: Some(CHAIN_ID.into()),
+            verifying_contract: Some(H160::from(SWORDS.0 .0)),
+            salt: None,
+        })
+    }
+
+    fn type_hash() -> Result<[u8; 32], Self::Error> {
+        Ok(keccak256("Kills(address player,uint256 count,uint256 nonce,uint256 deadline)"))
+    }
+
+    fn struct_hash(&self) -> Result<[u8; 32], Self::Error> {
+        Ok(keccak256(encode(&[
+            Token::FixedBytes(Self::type_hash()?.to_vec()),
+            Token::Address(self.player),
+            Token::Uint(self.count.into()),
+            Token::Uint(self.nonce.into()),
+            Token::Uint(self.deadline.into()),
+        ])))
+    }
+}
+
+thread_local! {
+    static GOLD_TOKEN: RefCell<Option<(TestVM, GoldToken)>> = const { RefCell::new(None) };
+}
+
+fn forward_to_gold(contract: Address, calldata: &[u8]) -> Result<Vec<u8>, Vec<u8>> {
+    assert_eq!(contract, GOLD, "the sword contract only calls the gold token");
+    let call = mintCall::abi_decode(calldata, true).expect("only mint is called");
+    GOLD_TOKEN.with(|token| {
+        let mut token = token.borrow_mut();
+        let (vm, gold) = token.as_mut().expect("gold token deployed");
+        vm.set_sender(SWORDS);
+        gold.mint(call.to, call.amount).map(|()| Vec::new()).map_err(Vec::from)
+    })
+}
+
+fn deploy() -> (TestVM, Counter) {
+    let gold_vm = TestVM::default();
+    gold_vm.set_contract_address(GOLD);
+    let mut gold = GoldToken::from(&gold_vm);
+    gold.constructor(SWORDS);
+    GOLD_TOKEN.with(|token| *token.borrow_mut() = Some((gold_vm, gold)));
+    common::CALL_HANDLER.with(|slot| *slot.borrow_mut() = Some(Box::new(forward_to_gold)));
+
+    let (vm, mut swords) = common::deploy();
+    vm.set_contract_address(SWORDS);
+    vm.set_chain_id(CHAIN_ID);
+    vm.set_block_timestamp(NOW);
+    swords.set_gold_token(GOLD).unwrap();
+    vm.set_sender(PLAYER);
+    (vm, swords)
+}
+
+fn gold_balance(owner: Address) -> U256 {
+    GOLD_TOKEN.with(|token| token.borrow().as_ref().unwrap().1.balance_of(owner))
+}
+
+#[test]
+fn pickups_work_without_a_gold_token() {
+    let (vm, mut swords) = common::deploy();
+    vm.set_sender(PLAYER);
+    swords.increment_sword(U256::from(RED)).unwrap();
+    assert_eq!(swords.balance_of(PLAYER, U256::from(RED)), U256::from(1));
+}
+
+#[test]
+fn only_the_owner_sets_the_gold_token() {
+    let (vm, mut swords) = deploy();
+
+    assert!(matches!(swords.set_gold_token(Address::ZERO), Err(CounterError::NotOwner(_))));
+    assert_eq!(swords.gold_token(), GOLD);
+
+    vm.set_sender(OWNER);
+    swords.set_gold_token(Address::ZERO).unwrap();
+    assert_eq!(swords.gold_token(), Address::ZERO);
+}
+
+#[test]
+fn each_pickup_mints_gold_to_the_player() {
+    let (_vm, mut swords) = deploy();
+
+    swords.increment_sword(U256::from(RED)).unwrap();
+    swords.increment_sword(U256::from(BLUE)).unwrap();
+
+    assert_eq!(gold_balance(PLAYER), GOLD_PER_PICKUP * U256::from(2));
+    assert_eq!(swords.balance_of(PLAYER, U256::from(BLUE)), U256::from(1));
+}
+
+#[tokio::test]
+async fn each_claimed_kill_mints_gold_to_the_player() {
+    let (vm, mut swords) = deploy();
+    let wallet = LocalWallet::from_bytes(&[0x11; 32]).unwrap().with_chain_id(CHAIN_ID);
+    let player = Address::from(wallet.address().0);
+    let kills = Kills { player: wallet.address(), count: 7, nonce: 0, deadline: NOW + 60 };
+
+    // TestVM has no precompiles, so answer ecrecover for exactly this claim.
+    let signature: Signature = wallet.sign_typed_data(&kills).await.unwrap();
+    let mut input = kills.encode_eip712().unwrap().to_vec();
+    input.extend(encode(&[Token::Uint(EthU256::from(signature.v)), Token::Uint(signature.r), Token::Uint(signature.s)]));
+    let mut output = vec![0u8; 12];
+    output.extend(wallet.address().as_bytes());
+    vm.mock_static_call(ECRECOVER, input, Ok(output));
+
+    swords
+        .claim_kills(player, U256::from(7), U256::ZERO, U256::from(NOW + 60), signature.to_vec().into())
+        .unwrap();
+
+    assert_eq!(swords.kills_of(player), U256::from(7));
+    assert_eq!(gold_balance(player), GOLD_PER_KILL * U256::from(7));
+}
+
+#[test]
+fn gold_cannot_be_minted_around_the_sword_contract() {
+    let _ = deploy();
+
+    let result = GOLD_TOKEN.with(|token| {
+        let mut token = token.borrow_mut();
+        let (vm, gold) = token.as_mut().unwrap();
+        vm.set_sender(PLAYER);
+        gold.mint(PLAYER, GOLD_PER_PICKUP)
+    });
+    assert!(matches!(result, Err(GoldError::NotMinter(_))));
+    assert_eq!(gold_balance(PLAYER), U256::ZERO);
+}
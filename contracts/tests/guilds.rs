@@ -0,0 +1,66 @@
+mod common;
+
+use common::deploy;
+use stylus_hello_world::{Counter, CounterError, BLUE, RED};
+use stylus_sdk::{
+    alloy_primitives::{Address, U256},
+    testing::*,
+};
+
+const ALICE: Address = Address::repeat_byte(0x01);
+const BOB: Address = Address::repeat_byte(0x02);
+const CAROL: Address = Address::repeat_byte(0x03);
+
+fn pick_up(vm: &TestVM, swords: &mut Counter, player: Address, count: usize) {
+    vm.set_sender(player);
+    for _ in 0..count {
+        swords.increment_sword(U256::from(RED)).unwrap();
+    }
+}
+
+#[test]
+fn guild_totals_follow_member_pickups() {
+    let (vm, mut swords) = deploy();
+
+    vm.set_sender(ALICE);
+    for tag in ["X", "TOOLONG", "abc", "A-B"] {
+        assert!(matches!(swords.create_guild(tag.into()), Err(CounterError::InvalidGuildTag(_))));
+    }
+    let knights = swords.create_guild("KNT".into()).unwrap();
+    assert!(matches!(swords.create_guild("RVL".into()), Err(CounterError::AlreadyInGuild(_))));
+
+    vm.set_sender(BOB);
+    assert!(matches!(swords.create_guild("KNT".into()), Err(CounterError::GuildTagTaken(_))));
+    assert!(matches!(swords.join_guild(U256::from(2)), Err(CounterError::UnknownGuild(_))));
+    swords.join_guild(knights).unwrap();
+    assert_eq!(swords.guild_by_tag("KNT".into()), knights);
+    assert_eq!(swords.get_guild_roster(knights).unwrap(), vec![ALICE, BOB]);
+
+    pick_up(&vm, &mut swords, ALICE, 2);
+    pick_up(&vm, &mut swords, BOB, 1);
+    assert_eq!(swords.get_guild(knights).unwrap(), ("KNT".to_string(), U256::from(2), U256::from(3)));
+
+    // Swords found before leaving stay with the guild; later ones do not.
+    vm.set_sender(ALICE);
+    swords.leave_guild().unwrap();
+    assert!(matches!(swords.leave_guild(), Err(CounterError::NotInGuild(_))));
+    swords.increment_sword(U256::from(BLUE)).unwrap();
+    assert_eq!(swords.guild_of(ALICE), U256::ZERO);
+    assert_eq!(swords.get_guild_roster(knights).unwrap(), vec![BOB]);
+    assert_eq!(swords.get_guild(knights).unwrap().2, U256::from(3));
+}
+
+#[test]
+fn leaderboard_ranks_guilds_by_total() {
+    let (vm, mut swords) = deploy();
+    for (player, tag) in [(ALICE, "AAA"), (BOB, "BBB"), (CAROL, "CCC")] {
+        vm.set_sender(player);
+        swords.create_guild(tag.into()).unwrap();
+    }
+    pick_up(&vm, &mut swords, BOB, 3);
+    pick_up(&vm, &mut swords, CAROL, 5);
+
+    let ids = |values: &[u64]| values.iter().map(|&value| U256::from(value)).collect::<Vec<_>>();
+    assert_eq!(swords.get_guild_leaderboard(U256::from(2)), (ids(&[3, 2]), ids(&[5, 3])));
+    assert_eq!(swords.get_guild_leaderboard(U256::from(10)).0, ids(&[3, 2, 1]));
+}
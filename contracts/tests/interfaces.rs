@@ -0,0 +1,51 @@
+//! Queries ERC-165 support through the router, as the game does at startup.
+//! The game hardcodes the ids it requires, so they are pinned here too.
+
+mod common;
+
+use alloy_sol_types::{sol, SolCall};
+use common::deploy;
+use stylus_hello_world::{forwarding_entrypoint, interfaces};
+use stylus_sdk::{host::VM, testing::*};
+
+sol! {
+    function supportsInterface(bytes4 interfaceId) returns (bool);
+    function version() returns (string);
+}
+
+fn supports(vm: &TestVM, id: [u8; 4]) -> bool {
+    let calldata = supportsInterfaceCall { interfaceId: id.into() }.abi_encode();
+    let output = forwarding_entrypoint(calldata, VM { host: Box::new(vm.clone()) }).unwrap();
+    supportsInterfaceCall::abi_decode_returns(&output, true).unwrap()._0
+}
+
+#[test]
+fn feature_sets_are_advertised() {
+    let (vm, _) = deploy();
+    for id in interfaces::SUPPORTED {
+        assert!(supports(&vm, id), "{}", hex::encode(id));
+    }
+    assert!(!supports(&vm, [0xff; 4]));
+
+    assert_eq!(interfaces::SWORDS, [0xfd, 0x04, 0x33, 0x79]);
+    assert_eq!(interfaces::NAMES, [0xfd, 0x4b, 0x36, 0xef]);
+    assert_eq!(interfaces::PICKUP_CLAIMS, [0xb1, 0x15, 0xa6, 0xea]);
+    assert_eq!(interfaces::KILLS, [0xf7, 0xb5, 0x05, 0x4e]);
+    assert_eq!(interfaces::SETTLEMENT, [0xfd, 0xf3, 0xc7, 0x8f]);
+    assert_eq!(interfaces::TOURNAMENTS, [0x0f, 0x4a, 0x2a, 0xab]);
+    assert_eq!(interfaces::SKINS, [0x46, 0x95, 0x94, 0x0c]);
+    assert_eq!(interfaces::DAILY_REWARD, [0x55, 0xb5, 0x01, 0xa9]);
+    assert_eq!(interfaces::GUILDS, [0xa2, 0xbb, 0x89, 0x24]);
+    let names = ["setName(string)", "nameOf(address)", "addressOf(string)"]
+        .iter()
+        .map(ethers::utils::id)
+        .fold([0u8; 4], |id, selector| std::array::from_fn(|byte| id[byte] ^ selector[byte]));
+    assert_eq!(interfaces::NAMES, names);
+}
+
+#[test]
+fn version_reports_the_crate_version() {
+    let (vm, _) = deploy();
+    let output = forwarding_entrypoint(versionCall {}.abi_encode(), VM { host: Box::new(vm.clone()) }).unwrap();
+    assert_eq!(versionCall::abi_decode_returns(&output, true).unwrap()._0, env!("CARGO_PKG_VERSION"));
+}
@@ -0,0 +1,47 @@
+mod common;
+
+use common::deploy;
+use stylus_hello_world::{CounterError, GREEN, KILL_RATIO_DECIMALS, RED};
+use stylus_sdk::alloy_primitives::{Address, U256};
+
+const ALICE: Address = Address::repeat_byte(0x01);
+const BOB: Address = Address::repeat_byte(0x02);
+
+fn ratio(kills: u64, swords: u64) -> U256 {
+    U256::from(kills) * U256::from(10).pow(U256::from(KILL_RATIO_DECIMALS)) / U256::from(swords)
+}
+
+#[test]
+fn kills_per_sword_counts_only_pickups() {
+    let (vm, mut swords) = deploy();
+    vm.set_sender(ALICE);
+    swords.record_kills(U256::from(5)).unwrap();
+    assert_eq!(swords.kills_per_sword(ALICE), ratio(5, 1));
+    swords.increment_sword(U256::from(RED)).unwrap();
+    swords.increment_sword(U256::from(GREEN)).unwrap();
+    // Gifted swords are not pickups and leave the ratio alone.
+    swords.transfer_swords(BOB, U256::from(RED), U256::from(1)).unwrap();
+    swords.claim_daily().unwrap();
+    assert_eq!(swords.pickups_of(ALICE), U256::from(2));
+    assert_eq!(swords.kills_per_sword(ALICE), ratio(5, 2));
+
+    vm.set_sender(BOB);
+    swords.record_kills(U256::from(1)).unwrap();
+    swords.increment_sword(U256::from(RED)).unwrap();
+    assert_eq!(swords.kills_per_sword(BOB), ratio(1, 1));
+
+    assert_eq!(swords.total_kills(), U256::from(6));
+    assert_eq!(swords.world_kills_per_sword(), ratio(6, 3));
+}
+
+#[test]
+fn direct_kills_close_once_a_threshold_is_set() {
+    let (vm, mut swords) = deploy();
+    swords.add_signer(BOB).unwrap();
+    swords.set_signer_threshold(U256::from(1)).unwrap();
+
+    vm.set_sender(ALICE);
+    assert!(matches!(swords.record_kills(U256::from(1_000)), Err(CounterError::InsufficientSignatures(_))));
+    assert_eq!(swords.kills_of(ALICE), U256::ZERO);
+    assert_eq!(swords.total_kills(), U256::ZERO);
+}
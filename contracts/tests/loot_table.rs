@@ -0,0 +1,47 @@
+mod common;
+
+use common::{deploy, OWNER, PLAYER};
+use stylus_hello_world::{CounterError, BLUE, COLOR_COUNT, NORMAL};
+use stylus_sdk::alloy_primitives::{keccak256, B256, U256};
+
+fn weights(table: (U256, U256, U256, U256)) -> [u64; 4] {
+    [table.0, table.1, table.2, table.3].map(|weight| weight.to::<u64>())
+}
+
+#[test]
+fn the_default_table_favours_colored_swords() {
+    let (_vm, swords) = deploy();
+    // Red, green, blue, normal.
+    assert_eq!(weights(swords.get_loot_table()), [3, 3, 3, 1]);
+}
+
+#[test]
+fn only_the_owner_sets_loot_weights() {
+    let (vm, mut swords) = deploy();
+    vm.set_sender(PLAYER);
+    assert!(matches!(
+        swords.set_loot_weight(U256::from(NORMAL), U256::from(50)),
+        Err(CounterError::NotOwner(_))
+    ));
+    assert_eq!(weights(swords.get_loot_table()), [3, 3, 3, 1]);
+
+    vm.set_sender(OWNER);
+    assert!(matches!(
+        swords.set_loot_weight(U256::from(COLOR_COUNT), U256::from(50)),
+        Err(CounterError::InvalidColor(_))
+    ));
+}
+
+#[test]
+fn updated_weights_show_in_the_loot_table() {
+    let (vm, mut swords) = deploy();
+    swords.set_loot_weight(U256::from(BLUE), U256::from(10)).unwrap();
+    swords.set_loot_weight(U256::from(NORMAL), U256::ZERO).unwrap();
+    assert_eq!(weights(swords.get_loot_table()), [3, 3, 10, 0]);
+
+    let signature = keccak256("LootWeightSet(uint256,uint256)");
+    let set: Vec<_> = vm.get_emitted_logs().into_iter().filter(|(topics, _)| topics[0] == signature).collect();
+    assert_eq!(set.len(), 2);
+    assert_eq!(set[0].0[1], B256::from(U256::from(BLUE)));
+    assert_eq!(U256::from_be_slice(&set[0].1), U256::from(10));
+}
@@ -0,0 +1,63 @@
+//! Drives ERC-2771 calls through the contract entrypoint, where the trusted
+//! forwarder's sender suffix is unwrapped.
+
+mod common;
+
+use alloy_sol_types::{sol, SolCall};
+use common::{OWNER, PLAYER};
+use stylus_hello_world::{forwarding_entrypoint, Counter, CounterError, BLUE};
+use stylus_sdk::{
+    alloy_primitives::{Address, U256},
+    host::VM,
+    testing::*,
+    ArbResult,
+};
+
+sol! {
+    function incrementSword(uint256 color);
+    function setName(string name);
+}
+
+const FORWARDER: Address = Address::repeat_byte(0xf0);
+
+fn deploy() -> (TestVM, Counter) {
+    let (vm, mut swords) = common::deploy();
+    swords.set_trusted_forwarder(FORWARDER).unwrap();
+    (vm, swords)
+}
+
+fn call(vm: &TestVM, mut calldata: Vec<u8>, suffix: Address) -> ArbResult {
+    calldata.extend_from_slice(suffix.as_slice());
+    forwarding_entrypoint(calldata, VM { host: Box::new(vm.clone()) })
+}
+
+#[test]
+fn forwarded_calls_act_for_the_original_sender() {
+    let (vm, swords) = deploy();
+    assert!(swords.is_trusted_forwarder(FORWARDER));
+    assert!(!swords.is_trusted_forwarder(Address::ZERO));
+
+    vm.set_sender(FORWARDER);
+    call(&vm, incrementSwordCall { color: U256::from(BLUE) }.abi_encode(), PLAYER).unwrap();
+    call(&vm, setNameCall { name: "relayed".into() }.abi_encode(), PLAYER).unwrap();
+
+    assert_eq!(swords.balance_of(PLAYER, U256::from(BLUE)), U256::from(1));
+    assert_eq!(swords.balance_of(FORWARDER, U256::from(BLUE)), U256::ZERO);
+    assert_eq!(swords.name_of(PLAYER), "relayed");
+}
+
+#[test]
+fn only_the_trusted_forwarder_can_append_a_sender() {
+    let (vm, mut swords) = deploy();
+
+    vm.set_sender(OWNER);
+    assert!(call(&vm, incrementSwordCall { color: U256::from(BLUE) }.abi_encode(), PLAYER).is_err());
+    assert_eq!(swords.balance_of(PLAYER, U256::from(BLUE)), U256::ZERO);
+
+    vm.set_sender(PLAYER);
+    assert!(matches!(swords.set_trusted_forwarder(PLAYER), Err(CounterError::NotOwner(_))));
+    vm.set_sender(OWNER);
+    swords.set_trusted_forwarder(Address::ZERO).unwrap();
+    vm.set_sender(FORWARDER);
+    assert!(call(&vm, incrementSwordCall { color: U256::from(BLUE) }.abi_encode(), PLAYER).is_err());
+}
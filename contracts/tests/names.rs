@@ -0,0 +1,82 @@
+mod common;
+
+use common::{deploy, PLAYER};
+use stylus_hello_world::{CounterError, NAME_MAX_LENGTH, NAME_MIN_LENGTH};
+use stylus_sdk::alloy_primitives::{keccak256, Address, B256, U256};
+
+const RIVAL: Address = Address::repeat_byte(0x02);
+
+#[test]
+fn names_must_fit_the_length_bounds() {
+    let (vm, mut swords) = deploy();
+    vm.set_sender(PLAYER);
+
+    for length in [0, NAME_MIN_LENGTH - 1, NAME_MAX_LENGTH + 1] {
+        let name = "a".repeat(length);
+        match swords.set_name(name) {
+            Err(CounterError::InvalidNameLength(error)) => assert_eq!(error.length, U256::from(length)),
+            other => panic!("length {}: {:?}", length, other),
+        }
+    }
+    swords.set_name("a".repeat(NAME_MIN_LENGTH)).unwrap();
+    swords.set_name("b".repeat(NAME_MAX_LENGTH)).unwrap();
+    assert_eq!(swords.name_of(PLAYER), "b".repeat(NAME_MAX_LENGTH));
+}
+
+#[test]
+fn names_are_lowercase_letters_digits_and_underscores() {
+    let (vm, mut swords) = deploy();
+    vm.set_sender(PLAYER);
+
+    for (name, index) in [("Sword", 0), ("sw ord", 2), ("swörd", 2), ("sword-1", 5)] {
+        match swords.set_name(name.into()) {
+            Err(CounterError::InvalidNameCharacter(error)) => assert_eq!(error.index, U256::from(index)),
+            other => panic!("{}: {:?}", name, other),
+        }
+    }
+    assert_eq!(swords.name_of(PLAYER), "");
+
+    swords.set_name("sword_42".into()).unwrap();
+    assert_eq!(swords.name_of(PLAYER), "sword_42");
+    assert_eq!(swords.address_of("sword_42".into()), PLAYER);
+}
+
+#[test]
+fn a_name_belongs_to_one_player() {
+    let (vm, mut swords) = deploy();
+    vm.set_sender(PLAYER);
+    swords.set_name("blade".into()).unwrap();
+    // Setting the same name again is a no-op rather than a clash with itself.
+    swords.set_name("blade".into()).unwrap();
+
+    vm.set_sender(RIVAL);
+    match swords.set_name("blade".into()) {
+        Err(CounterError::NameTaken(error)) => {
+            assert_eq!(error.name, "blade");
+            assert_eq!(error.owner, PLAYER);
+        }
+        other => panic!("{:?}", other),
+    }
+    assert_eq!(swords.name_of(RIVAL), "");
+    assert_eq!(swords.address_of("blade".into()), PLAYER);
+}
+
+#[test]
+fn renaming_releases_the_previous_name() {
+    let (vm, mut swords) = deploy();
+    vm.set_sender(PLAYER);
+    swords.set_name("blade".into()).unwrap();
+    swords.set_name("sabre".into()).unwrap();
+    assert_eq!(swords.address_of("blade".into()), Address::ZERO);
+    assert_eq!(swords.address_of("sabre".into()), PLAYER);
+
+    vm.set_sender(RIVAL);
+    swords.set_name("blade".into()).unwrap();
+    assert_eq!(swords.address_of("blade".into()), RIVAL);
+    assert_eq!(swords.name_of(PLAYER), "sabre");
+
+    let signature = keccak256("NameSet(address,string)");
+    let named: Vec<_> = vm.get_emitted_logs().into_iter().filter(|(topics, _)| topics[0] == signature).collect();
+    assert_eq!(named.len(), 3);
+    assert_eq!(named[2].0[1], B256::left_padding_from(RIVAL.as_slice()));
+}
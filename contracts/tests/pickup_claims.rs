@@ -0,0 +1,305 @@
+//! Checks that EIP-712 pickup and kill claims signed with `ethers`, as the game client
+//! does, are accepted by the contract. `TestVM` has no precompiles, so the
+//! `ecrecover` call is mocked for the exact input the contract must build.
+
+mod common;
+
+use common::OWNER;
+use ethers::{
+    abi::{encode, Token},
+    signers::{LocalWallet, Signer},
+    types::{
+        transaction::eip712::{EIP712Domain, Eip712},
+        Signature, H160, U256 as EthU256,
+    },
+    utils::keccak256,
+};
+use stylus_hello_world::{Counter, CounterError, GREEN};
+use stylus_sdk::{
+    alloy_primitives::{address, Address, U256},
+    testing::*,
+};
+
+const SWORDS: Address = Address::repeat_byte(0x5a);
+const RELAYER: Address = Address::repeat_byte(0x0e);
+const ECRECOVER: Address = address!("0000000000000000000000000000000000000001");
+const CHAIN_ID: u64 = 412346;
+const NOW: u64 = 1_700_000_000;
+
+struct Pickup {
+    player: H160,
+    color: u64,
+    amount: u64,
+    nonce: u64,
+    deadline: u64,
+}
+
+impl Eip712 for Pickup {
+    type Error = std::convert::Infallible;
+
+    fn domain(&self) -> Result<EIP712Domain, Self::Error> {
+        Ok(EIP712Domain {
+            name: Some("BevyStylusSwords".to_string()),
+            version: Some("1".to_string()),
+            chain_id: Some(CHAIN_ID.into()),
+            verifying_contract: Some(H160::from(SWORDS.0 .0)),
+            salt: None,
+        })
+    }
+
+    fn type_hash() -> Result<[u8; 32], Self::Error> {
+        Ok(keccak256(
+            "Pickup(address player,uint256 color,uint256 amount,uint256 nonce,uint256 deadline)",
+        ))
+    }
+
+    fn struct_hash(&self) -> Result<[u8; 32], Self::Error> {
+        Ok(keccak256(encode(&[
+            Token::FixedBytes(Self::type_hash()?.to_vec()),
+            Token::Address(self.player),
+            Token::Uint(self.color.into()),
+            Token::Uint(self.amount.into()),
+            Token::Uint(self.nonce.into()),
+            Token::Uint(self.deadline.into()),
+        ])))
+    }
+}
+
+struct Kills {
+    player: H160,
+    count: u64,
+    nonce: u64,
+    deadline: u64,
+}
+
+impl Eip712 for Kills {
+    type Error = std::convert::Infallible;
+
+    fn domain(&self) -> Result<EIP712Domain, Self::Error> {
+        Pickup { player: self.player, color: 0, amount: 0, nonce: 0, deadline: 0 }.domain()
+    }
+
+    fn type_hash() -> Result<[u8; 32], Self::Error> {
+        Ok(keccak256("Kills(address player,uint256 count,uint256 nonce,uint256 deadline)"))
+    }
+
+    fn struct_hash(&self) -> Result<[u8; 32], Self::Error> {
+        Ok(keccak256(encode(&[
+            Token::FixedBytes(Self::type_hash()?.to_vec()),
+            Token::Address(self.player),
+            Token::Uint(self.count.into()),
+            Token::Uint(self.nonce.into()),
+            Token::Uint(self.deadline.into()),
+        ])))
+    }
+}
+
+fn wallet() -> LocalWallet {
+    "0xb6b15c8cb491557369f3c7d2c287b053eb229daa9c22138887752191c9520659"
+        .parse::<LocalWallet>()
+        .unwrap()
+        .with_chain_id(CHAIN_ID)
+}
+
+fn deploy() -> (TestVM, Counter) {
+    let (vm, swords) = common::deploy();
+    vm.set_contract_address(SWORDS);
+    vm.set_chain_id(CHAIN_ID);
+    vm.set_block_timestamp(NOW);
+    vm.set_sender(RELAYER);
+    (vm, swords)
+}
+
+/// Signs `claim` and mocks `ecrecover` to answer for exactly that digest.
+async fn sign<T>(vm: &TestVM, wallet: &LocalWallet, claim: &T) -> Vec<u8>
+where
+    T: Eip712<Error = std::convert::Infallible> + Send + Sync,
+{
+    let signature: Signature = wallet.sign_typed_data(claim).await.unwrap();
+    let digest = claim.encode_eip712().unwrap();
+
+    let mut input = digest.to_vec();
+    input.extend(encode(&[
+        Token::Uint(EthU256::from(signature.v)),
+        Token::Uint(signature.r),
+        Token::Uint(signature.s),
+    ]));
+    let mut output = vec![0u8; 12];
+    output.extend(wallet.address().as_bytes());
+    vm.mock_static_call(ECRECOVER, input, Ok(output));
+
+    signature.to_vec()
+}
+
+fn player(wallet: &LocalWallet) -> Address {
+    Address::from(wallet.address().0)
+}
+
+fn attester(key: u8) -> LocalWallet {
+    LocalWallet::from_bytes(&[key; 32]).unwrap().with_chain_id(CHAIN_ID)
+}
+
+/// Registers `attesters` and requires `threshold` of them on every claim.
+fn require_attesters(vm: &TestVM, swords: &mut Counter, attesters: &[&LocalWallet], threshold: u64) {
+    vm.set_sender(OWNER);
+    for attester in attesters {
+        swords.add_signer(player(attester)).unwrap();
+    }
+    swords.set_signer_threshold(U256::from(threshold)).unwrap();
+    vm.set_sender(RELAYER);
+}
+
+fn claim_with(swords: &mut Counter, pickup: &Pickup, signatures: &[&[u8]]) -> Result<(), CounterError> {
+    swords.claim_pickup(
+        Address::from(pickup.player.0),
+        U256::from(pickup.color),
+        U256::from(pickup.amount),
+        U256::from(pickup.nonce),
+        U256::from(pickup.deadline),
+        signatures.concat().into(),
+    )
+}
+
+#[tokio::test]
+async fn relayed_claim_credits_the_signer() {
+    let (vm, mut swords) = deploy();
+    let wallet = wallet();
+    let pickup = Pickup { player: wallet.address(), color: 1, amount: 3, nonce: 0, deadline: NOW + 60 };
+    let signature = sign(&vm, &wallet, &pickup).await;
+
+    swords
+        .claim_pickup(player(&wallet), U256::from(GREEN), U256::from(3), U256::ZERO, U256::from(NOW + 60), signature.clone().into())
+        .unwrap();
+
+    assert_eq!(swords.balance_of(player(&wallet), U256::from(GREEN)), U256::from(3));
+    assert_eq!(swords.balance_of(RELAYER, U256::from(GREEN)), U256::ZERO);
+    assert_eq!(swords.nonces(player(&wallet)), U256::from(1));
+
+    assert!(matches!(
+        swords.claim_pickup(player(&wallet), U256::from(GREEN), U256::from(3), U256::ZERO, U256::from(NOW + 60), signature.into()),
+        Err(CounterError::InvalidNonce(_))
+    ));
+}
+
+#[tokio::test]
+async fn tampered_claims_are_rejected() {
+    let (vm, mut swords) = deploy();
+    let wallet = wallet();
+    let pickup = Pickup { player: wallet.address(), color: 1, amount: 1, nonce: 0, deadline: NOW + 60 };
+    let signature = sign(&vm, &wallet, &pickup).await;
+
+    // Any field change alters the digest, so ecrecover no longer yields the player.
+    assert!(matches!(
+        swords.claim_pickup(player(&wallet), U256::from(GREEN), U256::from(50), U256::ZERO, U256::from(NOW + 60), signature.clone().into()),
+        Err(CounterError::InvalidSignature(_))
+    ));
+
+    vm.set_block_timestamp(NOW + 61);
+    assert!(matches!(
+        swords.claim_pickup(player(&wallet), U256::from(GREEN), U256::from(1), U256::ZERO, U256::from(NOW + 60), signature.into()),
+        Err(CounterError::ClaimExpired(_))
+    ));
+    assert_eq!(swords.nonces(player(&wallet)), U256::ZERO);
+}
+
+#[tokio::test]
+async fn threshold_claims_need_distinct_registered_signers() {
+    let (vm, mut swords) = deploy();
+    let (first, second, outsider) = (attester(1), attester(2), attester(3));
+    require_attesters(&vm, &mut swords, &[&first, &second], 2);
+
+    let pickup = Pickup { player: wallet().address(), color: 1, amount: 1, nonce: 0, deadline: NOW + 60 };
+    let by_first = sign(&vm, &first, &pickup).await;
+    let by_second = sign(&vm, &second, &pickup).await;
+    let by_outsider = sign(&vm, &outsider, &pickup).await;
+    let by_player = sign(&vm, &wallet(), &pickup).await;
+
+    for signatures in [
+        vec![by_first.as_slice()],
+        vec![&by_first, &by_first],
+        vec![&by_first, &by_outsider],
+        vec![&by_player, &by_second],
+    ] {
+        assert!(matches!(
+            claim_with(&mut swords, &pickup, &signatures),
+            Err(CounterError::InsufficientSignatures(_))
+        ));
+    }
+    claim_with(&mut swords, &pickup, &[&by_outsider, &by_second, &by_first]).unwrap();
+    assert_eq!(swords.balance_of(player(&wallet()), U256::from(GREEN)), U256::from(1));
+}
+
+#[test]
+fn direct_pickups_close_once_a_threshold_is_set() {
+    let (vm, mut swords) = deploy();
+    swords.increment_sword(U256::from(GREEN)).unwrap();
+    require_attesters(&vm, &mut swords, &[&attester(1)], 1);
+
+    assert!(matches!(
+        swords.increment_sword(U256::from(GREEN)),
+        Err(CounterError::InsufficientSignatures(_))
+    ));
+    assert_eq!(swords.balance_of(RELAYER, U256::from(GREEN)), U256::from(1));
+}
+
+#[tokio::test]
+async fn rotating_a_signer_keeps_claims_valid() {
+    let (vm, mut swords) = deploy();
+    let (old, kept, new) = (attester(1), attester(2), attester(3));
+    require_attesters(&vm, &mut swords, &[&old, &kept], 2);
+
+    let pickup = Pickup { player: wallet().address(), color: 1, amount: 1, nonce: 0, deadline: NOW + 60 };
+    let signatures = [
+        sign(&vm, &old, &pickup).await,
+        sign(&vm, &kept, &pickup).await,
+        sign(&vm, &new, &pickup).await,
+    ];
+
+    vm.set_sender(OWNER);
+    assert!(matches!(swords.remove_signer(player(&old)), Err(CounterError::InvalidThreshold(_))));
+    swords.add_signer(player(&new)).unwrap();
+    swords.remove_signer(player(&old)).unwrap();
+    assert_eq!(swords.get_signers(), vec![player(&new), player(&kept)]);
+    assert!(matches!(swords.set_signer_threshold(U256::from(3)), Err(CounterError::InvalidThreshold(_))));
+
+    vm.set_sender(RELAYER);
+    claim_with(&mut swords, &pickup, &[&signatures[0], &signatures[1], &signatures[2]]).unwrap();
+    assert_eq!(swords.nonces(player(&wallet())), U256::from(1));
+}
+
+#[test]
+fn the_zero_address_cannot_be_a_signer() {
+    let (vm, mut swords) = deploy();
+    vm.set_sender(OWNER);
+    assert!(matches!(
+        swords.add_signer(Address::ZERO),
+        Err(CounterError::InvalidSigner(_))
+    ));
+    swords.add_signer(player(&attester(1))).unwrap();
+    assert!(matches!(
+        swords.add_signer(player(&attester(1))),
+        Err(CounterError::SignerAlreadyAdded(_))
+    ));
+    assert_eq!(swords.get_signers(), vec![player(&attester(1))]);
+}
+
+#[tokio::test]
+async fn kill_claims_share_the_pickup_nonce() {
+    let (vm, mut swords) = deploy();
+    let wallet = wallet();
+    let pickup = Pickup { player: wallet.address(), color: 1, amount: 1, nonce: 0, deadline: NOW + 60 };
+    let kills = Kills { player: wallet.address(), count: 7, nonce: 1, deadline: NOW + 60 };
+    let pickup_signature = sign(&vm, &wallet, &pickup).await;
+    let kills_signature = sign(&vm, &wallet, &kills).await;
+
+    let claim_kills = |swords: &mut Counter| {
+        swords.claim_kills(player(&wallet), U256::from(7), U256::from(1), U256::from(NOW + 60), kills_signature.clone().into())
+    };
+    assert!(matches!(claim_kills(&mut swords), Err(CounterError::InvalidNonce(_))));
+    claim_with(&mut swords, &pickup, &[&pickup_signature]).unwrap();
+    claim_kills(&mut swords).unwrap();
+
+    assert_eq!(swords.kills_of(player(&wallet)), U256::from(7));
+    assert_eq!(swords.nonces(player(&wallet)), U256::from(2));
+    assert!(matches!(claim_kills(&mut swords), Err(CounterError::InvalidNonce(_))));
+}
@@ -0,0 +1,32 @@
+mod common;
+
+use common::{deploy, OWNER, PLAYER};
+use stylus_hello_world::{CounterError, DEFAULT_SKIN};
+use stylus_sdk::alloy_primitives::U256;
+
+const ORIGINAL: U256 = U256::from_limbs([1, 0, 0, 0]);
+const GOLDEN: U256 = U256::from_limbs([2, 0, 0, 0]);
+
+#[test]
+fn only_granted_skins_can_be_equipped() {
+    let (vm, mut swords) = deploy();
+    assert!(swords.owns_skin(PLAYER, DEFAULT_SKIN));
+    assert_eq!(swords.equipped_skin(PLAYER), DEFAULT_SKIN);
+
+    vm.set_sender(PLAYER);
+    assert!(matches!(swords.grant_skin(PLAYER, ORIGINAL), Err(CounterError::NotOwner(_))));
+    assert!(matches!(swords.equip_skin(ORIGINAL), Err(CounterError::SkinNotOwned(_))));
+
+    vm.set_sender(OWNER);
+    swords.grant_skin(PLAYER, GOLDEN).unwrap();
+    swords.grant_skin(PLAYER, ORIGINAL).unwrap();
+    assert!(matches!(swords.grant_skin(PLAYER, ORIGINAL), Err(CounterError::SkinAlreadyOwned(_))));
+    assert!(matches!(swords.grant_skin(PLAYER, DEFAULT_SKIN), Err(CounterError::SkinAlreadyOwned(_))));
+    assert_eq!(swords.get_owned_skins(PLAYER), vec![GOLDEN, ORIGINAL]);
+
+    vm.set_sender(PLAYER);
+    swords.equip_skin(ORIGINAL).unwrap();
+    assert_eq!(swords.equipped_skin(PLAYER), ORIGINAL);
+    swords.equip_skin(DEFAULT_SKIN).unwrap();
+    assert_eq!(swords.equipped_skin(PLAYER), DEFAULT_SKIN);
+}
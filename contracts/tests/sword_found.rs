@@ -0,0 +1,23 @@
+//! Every pickup announces itself with `SwordFound`, which the game polls to
+//! show other players' finds.
+
+mod common;
+
+use common::{deploy, PLAYER};
+use stylus_hello_world::BLUE;
+use stylus_sdk::alloy_primitives::{keccak256, B256, U256};
+
+#[test]
+fn pickups_emit_sword_found() {
+    let (vm, mut swords) = deploy();
+    vm.set_sender(PLAYER);
+    swords.increment_sword(U256::from(BLUE)).unwrap();
+
+    let signature = keccak256("SwordFound(address,uint256,uint256)");
+    let found: Vec<_> = vm.get_emitted_logs().into_iter().filter(|(topics, _)| topics[0] == signature).collect();
+    assert_eq!(found.len(), 1);
+    let (topics, data) = &found[0];
+    assert_eq!(topics[1], B256::left_padding_from(PLAYER.as_slice()));
+    assert_eq!(topics[2], B256::from(U256::from(BLUE)));
+    assert_eq!(U256::from_be_slice(data), U256::from(1));
+}
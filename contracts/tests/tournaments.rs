@@ -0,0 +1,112 @@
+mod common;
+
+use stylus_hello_world::{Counter, CounterError, RED};
+use stylus_sdk::{
+    alloy_primitives::{Address, U256},
+    testing::*,
+};
+
+const SWORDS: Address = Address::repeat_byte(0x5a);
+const ALICE: Address = Address::repeat_byte(0x01);
+const BOB: Address = Address::repeat_byte(0x02);
+const CAROL: Address = Address::repeat_byte(0x03);
+const START: u64 = 1_700_000_000;
+const END: u64 = START + 3600;
+const FEE: u64 = 1_000;
+
+fn deploy() -> (TestVM, Counter) {
+    let (vm, mut swords) = common::deploy();
+    vm.set_contract_address(SWORDS);
+    vm.set_block_timestamp(START - 60);
+    let percentages = vec![U256::from(70), U256::from(30)];
+    let id = swords.create_tournament(U256::from(FEE), U256::from(START), U256::from(END), percentages).unwrap();
+    assert_eq!(id, U256::from(1));
+    (vm, swords)
+}
+
+fn join(vm: &TestVM, swords: &mut Counter, player: Address, fee: u64) -> Result<(), CounterError> {
+    vm.set_sender(player);
+    vm.set_value(U256::from(fee));
+    let result = swords.join_tournament(U256::from(1));
+    vm.set_value(U256::ZERO);
+    result
+}
+
+fn pick_up(vm: &TestVM, swords: &mut Counter, player: Address, count: usize) {
+    vm.set_sender(player);
+    for _ in 0..count {
+        swords.increment_sword(U256::from(RED)).unwrap();
+    }
+}
+
+#[test]
+fn only_the_owner_creates_valid_tournaments() {
+    let (vm, mut swords) = deploy();
+    let payouts = vec![U256::from(60), U256::from(30)];
+
+    assert!(matches!(
+        swords.create_tournament(U256::ZERO, U256::from(START), U256::from(END), payouts.clone()),
+        Err(CounterError::InvalidPayouts(_))
+    ));
+    assert!(matches!(
+        swords.create_tournament(U256::ZERO, U256::from(END), U256::from(START), vec![U256::from(100)]),
+        Err(CounterError::InvalidTournamentWindow(_))
+    ));
+    vm.set_sender(ALICE);
+    assert!(matches!(
+        swords.create_tournament(U256::ZERO, U256::from(START), U256::from(END), vec![U256::from(100)]),
+        Err(CounterError::NotOwner(_))
+    ));
+    assert_eq!(swords.tournament_count(), U256::from(1));
+}
+
+#[test]
+fn entry_requires_the_exact_fee_and_one_tournament_at_a_time() {
+    let (vm, mut swords) = deploy();
+
+    assert!(matches!(join(&vm, &mut swords, ALICE, FEE - 1), Err(CounterError::WrongEntryFee(_))));
+    join(&vm, &mut swords, ALICE, FEE).unwrap();
+    assert!(matches!(join(&vm, &mut swords, ALICE, FEE), Err(CounterError::AlreadyInTournament(_))));
+
+    vm.set_block_timestamp(END);
+    assert!(matches!(join(&vm, &mut swords, BOB, FEE), Err(CounterError::TournamentClosed(_))));
+    let (_, _, _, prize_pool, entrants, _) = swords.get_tournament(U256::from(1)).unwrap();
+    assert_eq!((prize_pool, entrants), (U256::from(FEE), U256::from(1)));
+}
+
+#[test]
+fn top_finishers_split_the_pool() {
+    let (vm, mut swords) = deploy();
+    for player in [ALICE, BOB, CAROL] {
+        join(&vm, &mut swords, player, FEE).unwrap();
+    }
+    vm.set_balance(SWORDS, U256::from(3 * FEE));
+
+    // Only pickups inside the window count toward the tournament.
+    pick_up(&vm, &mut swords, CAROL, 5);
+    vm.set_block_timestamp(START);
+    pick_up(&vm, &mut swords, ALICE, 2);
+    pick_up(&vm, &mut swords, BOB, 3);
+    pick_up(&vm, &mut swords, CAROL, 1);
+    assert_eq!(swords.tournament_score(U256::from(1), CAROL), U256::from(1));
+    assert_eq!(swords.balance_of(CAROL, U256::from(RED)), U256::from(6));
+
+    assert!(matches!(swords.finalize_tournament(U256::from(1)), Err(CounterError::TournamentNotOver(_))));
+    vm.set_block_timestamp(END);
+    let (standings, _) = swords.get_tournament_standings(U256::from(1)).unwrap();
+    assert_eq!(standings, vec![BOB, ALICE, CAROL]);
+    swords.finalize_tournament(U256::from(1)).unwrap();
+    assert!(matches!(
+        swords.finalize_tournament(U256::from(1)),
+        Err(CounterError::TournamentAlreadyFinalized(_))
+    ));
+
+    assert_eq!(swords.prize_balance(BOB), U256::from(2100));
+    assert_eq!(swords.prize_balance(ALICE), U256::from(900));
+    assert_eq!(swords.prize_balance(CAROL), U256::ZERO);
+
+    vm.set_sender(BOB);
+    swords.withdraw_prizes().unwrap();
+    assert_eq!(vm.balance(BOB), U256::from(2100));
+    assert_eq!(swords.prize_balance(BOB), U256::ZERO);
+}
@@ -0,0 +1,63 @@
+mod common;
+
+use common::{deploy, PLAYER};
+use stylus_hello_world::{CounterError, BLUE, RED};
+use stylus_sdk::alloy_primitives::{keccak256, Address, B256, U256};
+
+const FRIEND: Address = Address::repeat_byte(0x02);
+
+#[test]
+fn swords_move_to_the_recipient() {
+    let (vm, mut swords) = deploy();
+    vm.set_sender(PLAYER);
+    swords.increment_sword(U256::from(BLUE)).unwrap();
+    swords.increment_sword(U256::from(BLUE)).unwrap();
+
+    swords.transfer_swords(FRIEND, U256::from(BLUE), U256::from(2)).unwrap();
+    assert_eq!(swords.balance_of(PLAYER, U256::from(BLUE)), U256::ZERO);
+    assert_eq!(swords.balance_of(FRIEND, U256::from(BLUE)), U256::from(2));
+
+    let signature = keccak256("SwordsTransferred(address,address,uint256,uint256)");
+    let transfers: Vec<_> = vm.get_emitted_logs().into_iter().filter(|(topics, _)| topics[0] == signature).collect();
+    assert_eq!(transfers.len(), 1);
+    let (topics, data) = &transfers[0];
+    assert_eq!(topics[1], B256::left_padding_from(PLAYER.as_slice()));
+    assert_eq!(topics[2], B256::left_padding_from(FRIEND.as_slice()));
+    assert_eq!(topics[3], B256::from(U256::from(BLUE)));
+    assert_eq!(U256::from_be_slice(data), U256::from(2));
+}
+
+#[test]
+fn players_cannot_give_more_than_they_hold() {
+    let (vm, mut swords) = deploy();
+    vm.set_sender(PLAYER);
+    swords.increment_sword(U256::from(RED)).unwrap();
+
+    match swords.transfer_swords(FRIEND, U256::from(RED), U256::from(2)) {
+        Err(CounterError::InsufficientSwords(error)) => {
+            assert_eq!(error.color, U256::from(RED));
+            assert_eq!(error.balance, U256::from(1));
+            assert_eq!(error.requested, U256::from(2));
+        }
+        other => panic!("{:?}", other),
+    }
+    assert_eq!(swords.balance_of(PLAYER, U256::from(RED)), U256::from(1));
+    assert_eq!(swords.balance_of(FRIEND, U256::from(RED)), U256::ZERO);
+}
+
+#[test]
+fn swords_need_a_recipient_other_than_the_sender() {
+    let (vm, mut swords) = deploy();
+    vm.set_sender(PLAYER);
+    swords.increment_sword(U256::from(RED)).unwrap();
+
+    for to in [Address::ZERO, PLAYER] {
+        match swords.transfer_swords(to, U256::from(RED), U256::from(1)) {
+            Err(CounterError::InvalidRecipient(error)) => assert_eq!(error.to, to),
+            other => panic!("{}: {:?}", to, other),
+        }
+    }
+    assert_eq!(swords.balance_of(PLAYER, U256::from(RED)), U256::from(1));
+    let signature = keccak256("SwordsTransferred(address,address,uint256,uint256)");
+    assert!(vm.get_emitted_logs().iter().all(|(topics, _)| topics[0] != signature));
+}
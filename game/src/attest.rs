@@ -0,0 +1,83 @@
+use std::time::Duration;
+
+use ethers::types::Bytes;
+use serde::{Deserialize, Serialize};
+
+use crate::claims::{KillsClaim, PickupClaim};
+
+/// Comma-separated signer service urls, asked in order.
+const ATTESTATION_URLS: &str = "ATTESTATION_URLS";
+/// One slow signer should not hold up the whole claim.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// The typed data a signer service is asked to attest. It carries the claim's
+/// nonce and deadline, so an attestation is only good for that one claim.
+#[derive(Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Attestation<'a> {
+    Pickup(&'a PickupClaim),
+    Kills(&'a KillsClaim),
+}
+
+#[derive(Deserialize)]
+struct AttestResponse {
+    signature: Bytes,
+}
+
+/// Collects signer service signatures for claims once the contract has a
+/// signer threshold. Present only when `ATTESTATION_URLS` is set.
+#[derive(Clone)]
+pub struct AttestClient {
+    urls: Vec<String>,
+    http: reqwest::Client,
+}
+
+impl AttestClient {
+    pub fn from_env() -> Option<Self> {
+        let urls: Vec<String> = std::env::var(ATTESTATION_URLS)
+            .ok()?
+            .split(',')
+            .map(|url| url.trim().trim_end_matches('/').to_string())
+            .filter(|url| !url.is_empty())
+            .collect();
+        if urls.is_empty() {
+            return None;
+        }
+        let http = reqwest::Client::builder().timeout(REQUEST_TIMEOUT).build().ok()?;
+        Some(AttestClient { urls, http })
+    }
+
+    /// Asks every signer service for `attestation`. All answers are kept, since
+    /// the contract skips keys it no longer knows and a service mid-rotation
+    /// may still sign with a removed one. Fails with fewer than `needed`.
+    pub async fn attest(&self, attestation: &Attestation<'_>, needed: usize) -> Result<Vec<Bytes>, String> {
+        let mut signatures = Vec::new();
+        let mut errors = Vec::new();
+        for url in &self.urls {
+            match self.request(url, attestation).await {
+                Ok(signature) => signatures.push(signature),
+                Err(err) => errors.push(format!("{}: {}", url, err)),
+            }
+        }
+        if signatures.len() < needed {
+            return Err(format!("{} of {} attestations ({})", signatures.len(), needed, errors.join("; ")));
+        }
+        Ok(signatures)
+    }
+
+    async fn request(&self, url: &str, attestation: &Attestation<'_>) -> eyre::Result<Bytes> {
+        let response: AttestResponse = self
+            .http
+            .post(format!("{}/attest", url))
+            .json(attestation)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        if response.signature.len() != 65 {
+            eyre::bail!("expected a 65-byte signature, got {} bytes", response.signature.len());
+        }
+        Ok(response.signature)
+    }
+}
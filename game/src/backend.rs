@@ -0,0 +1,122 @@
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::mpsc::Sender;
+use std::sync::Arc;
+
+use bevy::prelude::*;
+use ethers::types::{Address, H256, U256};
+
+use crate::menu::GuildStatus;
+use crate::tournament::TournamentInfo;
+use crate::SWORD_COLORS;
+
+/// Futures are boxed so backends can be used as trait objects; they run on
+/// the chain runtime and own everything they touch.
+pub type BackendFuture<T> = Pin<Box<dyn Future<Output = T> + Send>>;
+
+/// A state-changing call. `TxQueue` decides when to send it; the backend
+/// decides how.
+#[derive(Clone, Debug)]
+pub enum ChainCall {
+    /// One picked-up sword, recorded for the local player.
+    IncrementSword { color: u8 },
+    ClaimKills { count: u64 },
+    TransferSwords { to: Address, color: u8, amount: u64 },
+    SetName(String),
+    ClaimDaily,
+    /// Swords from a batch settlement; `proof` shows `counts` are in the posted root.
+    #[cfg(feature = "chain")]
+    ClaimSettlement { proof: Vec<[u8; 32]>, counts: [u64; SWORD_COLORS] },
+    /// Pays `entry_fee`, so it is never relayed.
+    JoinTournament { id: u64, entry_fee: U256 },
+}
+
+pub enum SendError {
+    /// The call would revert; carries the decoded reason.
+    Reverted(String),
+    Failed(String),
+}
+
+/// What became of a sent call.
+pub enum TxCheck {
+    /// Mined in `H256`; `Err` carries the revert reason.
+    Mined(H256, Result<(), String>),
+    Pending,
+    /// Still unmined, so it went out again with higher fees.
+    Resent(H256),
+    /// The replacement was refused, usually because the original just got mined.
+    ResendFailed(String),
+}
+
+/// A sword picked up anywhere in the world.
+#[derive(Event, Clone, Debug)]
+pub struct SwordFound {
+    /// `None` when the find was inferred from a change in world totals.
+    pub player: Option<Address>,
+    pub name: Option<String>,
+    pub color: u8,
+    pub amount: u64,
+}
+
+/// The local player's state as of connecting. Reads that fail are left out
+/// and the local value stands.
+#[derive(Default)]
+pub struct PlayerSnapshot {
+    pub swords: Option<[u64; SWORD_COLORS]>,
+    pub loot_weights: Option<[u64; SWORD_COLORS]>,
+    pub name: Option<String>,
+    pub guild: Option<GuildStatus>,
+    pub kills: Option<u64>,
+    pub equipped_skin: Option<u64>,
+    pub next_daily_claim: Option<u64>,
+    /// Settled swords the player has yet to claim.
+    pub settlement: Option<ChainCall>,
+}
+
+/// Everything the game asks of the sword contract. Game systems only see
+/// this trait, through the [`Backend`] resource, so the same code runs
+/// against a node or against [`MockBackend`](crate::mock_backend::MockBackend).
+pub trait ChainBackend: Send + Sync + 'static {
+    /// The local player's account.
+    fn player(&self) -> Address;
+
+    /// Whether this backend only lives in memory.
+    fn is_local(&self) -> bool {
+        false
+    }
+
+    /// Makes sure the backend can serve this build of the game.
+    fn check_compatible(&self) -> BackendFuture<Result<(), String>>;
+
+    fn load_player(&self) -> BackendFuture<PlayerSnapshot>;
+
+    /// World-wide totals per color.
+    fn get_sword_counts(&self) -> BackendFuture<Result<[u64; SWORD_COLORS], String>>;
+
+    /// The local player's swords per color.
+    fn get_player_sword_counts(&self) -> BackendFuture<Result<[u64; SWORD_COLORS], String>>;
+
+    /// Tournaments that have not ended yet.
+    fn tournaments(&self) -> BackendFuture<Result<Vec<TournamentInfo>, String>>;
+
+    /// Id of the tournament the local player is in; 0 for none.
+    fn current_tournament(&self) -> BackendFuture<Result<u64, String>>;
+
+    fn submit(&self, call: ChainCall) -> BackendFuture<Result<H256, SendError>>;
+
+    /// Looks for a receipt for any of `hashes`, all sent for one call. When
+    /// `stuck` and unmined, the latest one is re-sent with higher fees if the
+    /// backend can.
+    fn check(&self, hashes: Vec<H256>, stuck: bool) -> BackendFuture<Result<TxCheck, String>>;
+
+    /// The queue stopped waiting for `hashes`, which were likely dropped.
+    fn abandon(&self, _hashes: Vec<H256>) {}
+
+    /// Reports finds by every player to `found` until the receiver is dropped.
+    fn subscribe(&self, found: Sender<SwordFound>) -> BackendFuture<()>;
+}
+
+/// The connected backend. Absent until connecting succeeds, and for good
+/// when chain features are disabled.
+#[derive(Resource, Clone, Deref)]
+pub struct Backend(pub Arc<dyn ChainBackend>);
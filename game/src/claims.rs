@@ -0,0 +1,153 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use ethers::abi::{encode, Token};
+use ethers::signers::{LocalWallet, Signer};
+use ethers::types::transaction::eip712::{EIP712Domain, Eip712};
+use ethers::types::{Address, Bytes, H256, U256};
+use ethers::utils::keccak256;
+use serde::Serialize;
+
+/// Must match the contract's EIP-712 domain or every claim is rejected.
+const DOMAIN_NAME: &str = "BevyStylusSwords";
+const DOMAIN_VERSION: &str = "1";
+const PICKUP_TYPE: &str =
+    "Pickup(address player,uint256 color,uint256 amount,uint256 nonce,uint256 deadline)";
+const KILLS_TYPE: &str = "Kills(address player,uint256 count,uint256 nonce,uint256 deadline)";
+
+/// How long a signed claim stays valid; long enough for a relayer to pick it up.
+const CLAIM_TTL_SECS: u64 = 10 * 60;
+
+/// A pickup signed so anyone can submit it with `claimPickup`. The player's own
+/// signature is accepted until the owner sets a signer threshold; after that,
+/// signer services attest the same typed data through `AttestClient` and their
+/// signatures are sent instead.
+#[derive(Clone, Debug, Serialize)]
+pub struct PickupClaim {
+    pub player: Address,
+    pub color: U256,
+    pub amount: U256,
+    pub nonce: U256,
+    pub deadline: U256,
+    pub chain_id: u64,
+    pub contract: Address,
+}
+
+impl Eip712 for PickupClaim {
+    type Error = std::convert::Infallible;
+
+    fn domain(&self) -> Result<EIP712Domain, Self::Error> {
+        Ok(domain(self.chain_id, self.contract))
+    }
+
+    fn type_hash() -> Result<[u8; 32], Self::Error> {
+        Ok(keccak256(PICKUP_TYPE))
+    }
+
+    fn struct_hash(&self) -> Result<[u8; 32], Self::Error> {
+        Ok(keccak256(encode(&[
+            Token::FixedBytes(Self::type_hash()?.to_vec()),
+            Token::Address(self.player),
+            Token::Uint(self.color),
+            Token::Uint(self.amount),
+            Token::Uint(self.nonce),
+            Token::Uint(self.deadline),
+        ])))
+    }
+}
+
+/// A batch of kills, signed and attested exactly like a [`PickupClaim`] and
+/// submitted with `claimKills`. Both kinds of claim share the player's nonce.
+#[derive(Clone, Debug, Serialize)]
+pub struct KillsClaim {
+    pub player: Address,
+    pub count: U256,
+    pub nonce: U256,
+    pub deadline: U256,
+    pub chain_id: u64,
+    pub contract: Address,
+}
+
+impl Eip712 for KillsClaim {
+    type Error = std::convert::Infallible;
+
+    fn domain(&self) -> Result<EIP712Domain, Self::Error> {
+        Ok(domain(self.chain_id, self.contract))
+    }
+
+    fn type_hash() -> Result<[u8; 32], Self::Error> {
+        Ok(keccak256(KILLS_TYPE))
+    }
+
+    fn struct_hash(&self) -> Result<[u8; 32], Self::Error> {
+        Ok(keccak256(encode(&[
+            Token::FixedBytes(Self::type_hash()?.to_vec()),
+            Token::Address(self.player),
+            Token::Uint(self.count),
+            Token::Uint(self.nonce),
+            Token::Uint(self.deadline),
+        ])))
+    }
+}
+
+fn domain(chain_id: u64, contract: Address) -> EIP712Domain {
+    EIP712Domain {
+        name: Some(DOMAIN_NAME.to_string()),
+        version: Some(DOMAIN_VERSION.to_string()),
+        chain_id: Some(chain_id.into()),
+        verifying_contract: Some(contract),
+        salt: None,
+    }
+}
+
+/// Signs pickup and kill claims for the local player. Claims are signed just
+/// before they are sent, with the contract's current `nonces(player)`; the
+/// transaction queue sends one at a time, so that nonce is always the next one.
+#[derive(Clone)]
+pub struct PickupSigner {
+    wallet: LocalWallet,
+    contract: Address,
+}
+
+impl PickupSigner {
+    pub fn new(wallet: LocalWallet, contract: Address) -> Self {
+        PickupSigner { wallet, contract }
+    }
+
+    pub fn sign(&self, color: u8, amount: u64, nonce: U256) -> eyre::Result<(PickupClaim, Bytes)> {
+        let claim = PickupClaim {
+            player: self.wallet.address(),
+            color: U256::from(color),
+            amount: U256::from(amount),
+            nonce,
+            deadline: deadline()?,
+            chain_id: self.wallet.chain_id(),
+            contract: self.contract,
+        };
+        let signature = self.sign_typed(&claim)?;
+        Ok((claim, signature))
+    }
+
+    pub fn sign_kills(&self, count: u64, nonce: U256) -> eyre::Result<(KillsClaim, Bytes)> {
+        let claim = KillsClaim {
+            player: self.wallet.address(),
+            count: U256::from(count),
+            nonce,
+            deadline: deadline()?,
+            chain_id: self.wallet.chain_id(),
+            contract: self.contract,
+        };
+        let signature = self.sign_typed(&claim)?;
+        Ok((claim, signature))
+    }
+
+    fn sign_typed<T: Eip712<Error = std::convert::Infallible>>(&self, claim: &T) -> eyre::Result<Bytes> {
+        let digest = claim.encode_eip712()?;
+        let signature = self.wallet.sign_hash(H256(digest))?;
+        Ok(signature.to_vec().into())
+    }
+}
+
+fn deadline() -> eyre::Result<U256> {
+    let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+    Ok(U256::from(now + CLAIM_TTL_SECS))
+}
@@ -0,0 +1,112 @@
+use bevy::prelude::*;
+
+/// ERC-165 ids of every feature set the game calls into. A test recomputes
+/// them from the selector lists in `contracts/src/interfaces.rs`.
+#[cfg(any(feature = "chain", test))]
+const REQUIRED_INTERFACES: [(&str, [u8; 4]); 9] = [
+    ("swords", [0xfd, 0x04, 0x33, 0x79]),
+    ("names", [0xfd, 0x4b, 0x36, 0xef]),
+    ("pickup claims", [0xb1, 0x15, 0xa6, 0xea]),
+    ("kills", [0xf7, 0xb5, 0x05, 0x4e]),
+    ("batch settlement", [0xfd, 0xf3, 0xc7, 0x8f]),
+    ("tournaments", [0x0f, 0x4a, 0x2a, 0xab]),
+    ("skins", [0x46, 0x95, 0x94, 0x0c]),
+    ("daily rewards", [0x55, 0xb5, 0x01, 0xa9]),
+    ("guilds", [0xa2, 0xbb, 0x89, 0x24]),
+];
+
+/// Any `0.2.x` contract exposes the ABI the game calls; patch releases do not change it.
+#[cfg(feature = "chain")]
+const SUPPORTED_VERSION: (u64, u64) = (0, 2);
+/// The first release that emits `SwordFound`.
+#[cfg(feature = "chain")]
+const SWORD_FOUND_VERSION: (u64, u64, u64) = (0, 2, 1);
+
+/// Why chain features were turned off at startup, shown on the main menu.
+#[derive(Resource, Default)]
+pub struct ChainStatus {
+    pub disabled_reason: Option<String>,
+}
+
+#[cfg(feature = "chain")]
+pub fn required_interfaces() -> impl Iterator<Item = (&'static str, [u8; 4])> {
+    REQUIRED_INTERFACES.into_iter()
+}
+
+/// Reads `major.minor.patch`, treating a missing patch as 0.
+#[cfg(feature = "chain")]
+fn parse_version(version: &str) -> Option<(u64, u64, u64)> {
+    let mut parts = version.split('.').map(|part| part.parse::<u64>().ok());
+    match (parts.next(), parts.next(), parts.next()) {
+        (Some(Some(major)), Some(Some(minor)), None) => Some((major, minor, 0)),
+        (Some(Some(major)), Some(Some(minor)), Some(Some(patch))) => Some((major, minor, patch)),
+        _ => None,
+    }
+}
+
+#[cfg(feature = "chain")]
+pub fn emits_sword_found(version: &str) -> bool {
+    parse_version(version).is_some_and(|version| version >= SWORD_FOUND_VERSION)
+}
+
+/// Checks the `version()` string of the deployed contract.
+#[cfg(feature = "chain")]
+pub fn check_version(version: &str) -> Result<(), String> {
+    let Some((major, minor, _)) = parse_version(version) else {
+        return Err(format!("Contract reports an unreadable version \"{}\"", version));
+    };
+    if (major, minor) != SUPPORTED_VERSION {
+        return Err(format!(
+            "Contract version {} is not supported; this game needs {}.{}.x",
+            version, SUPPORTED_VERSION.0, SUPPORTED_VERSION.1
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethers::utils::keccak256;
+
+    /// The contract-side constant behind each feature set the game requires.
+    const CONTRACT_CONSTANTS: [(&str, &str); 9] = [
+        ("swords", "SWORDS"),
+        ("names", "NAMES"),
+        ("pickup claims", "PICKUP_CLAIMS"),
+        ("kills", "KILLS"),
+        ("batch settlement", "SETTLEMENT"),
+        ("tournaments", "TOURNAMENTS"),
+        ("skins", "SKINS"),
+        ("daily rewards", "DAILY_REWARD"),
+        ("guilds", "GUILDS"),
+    ];
+
+    /// Reads the signatures listed for `constant` in `interfaces.rs`.
+    fn contract_signatures(source: &str, constant: &str) -> Vec<String> {
+        let start = format!("pub const {}: [u8; 4] = interface_id(&[", constant);
+        let list = source.split_once(&start).unwrap_or_else(|| panic!("{} not in interfaces.rs", constant)).1;
+        let list = list.split_once("]);").unwrap().0;
+        list.split('"').skip(1).step_by(2).map(str::to_string).collect()
+    }
+
+    fn interface_id(signatures: &[String]) -> [u8; 4] {
+        signatures.iter().fold([0; 4], |mut id, signature| {
+            for (byte, selector) in id.iter_mut().zip(keccak256(signature)) {
+                *byte ^= selector;
+            }
+            id
+        })
+    }
+
+    #[test]
+    fn required_ids_match_the_contract_selector_lists() {
+        let source = include_str!("../../contracts/src/interfaces.rs");
+        for ((name, id), (label, constant)) in REQUIRED_INTERFACES.into_iter().zip(CONTRACT_CONSTANTS) {
+            assert_eq!(name, label);
+            let signatures = contract_signatures(source, constant);
+            assert!(!signatures.is_empty(), "{} lists no selectors", constant);
+            assert_eq!(id, interface_id(&signatures), "{} drifted from interfaces::{}", name, constant);
+        }
+    }
+}
@@ -0,0 +1,138 @@
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use bevy::prelude::*;
+use ethers::providers::{Http, Provider};
+#[cfg(feature = "dev-keys")]
+use ethers::signers::LocalWallet;
+use ethers::types::Address;
+use serde::Deserialize;
+#[cfg(feature = "dev-keys")]
+use zeroize::Zeroizing;
+
+use crate::data_dir;
+
+/// Written by `cargo stylus deploy`; holds the contract address and RPC url.
+const CONFIG_FILE: &str = "Stylus.toml";
+
+/// Stylus RPC endpoint url; overrides `contract.rpc_url`.
+const RPC_URL: &str = "RPC_URL";
+
+/// Deployed sword contract address; overrides `contract.address`.
+const STYLUS_CONTRACT_ADDRESS: &str = "STYLUS_CONTRACT_ADDRESS";
+
+/// Plaintext key of the player's account, honoured only in `dev-keys` builds.
+/// Never read from `Stylus.toml`.
+const PRIVATE_KEY: &str = "PRIVATE_KEY";
+
+/// Encrypted keystore location; defaults to `keystore.json` in the data dir.
+const KEYSTORE_PATH: &str = "KEYSTORE_PATH";
+const KEYSTORE_FILE: &str = "keystore.json";
+
+#[derive(Deserialize, Default)]
+struct StylusToml {
+    #[serde(default)]
+    contract: ContractSection,
+}
+
+#[derive(Deserialize, Default)]
+struct ContractSection {
+    address: Option<String>,
+    rpc_url: Option<String>,
+    network: Option<String>,
+}
+
+#[derive(Debug)]
+pub enum ConfigError {
+    Read(PathBuf, std::io::Error),
+    Parse(PathBuf, toml::de::Error),
+    Missing { key: &'static str, env: &'static str },
+    InvalidAddress(String),
+    InvalidRpcUrl(String),
+    #[cfg(feature = "dev-keys")]
+    InvalidPrivateKey,
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigError::Read(path, err) => write!(f, "could not read {}: {}", path.display(), err),
+            ConfigError::Parse(path, err) => write!(f, "{} is not valid TOML: {}", path.display(), err),
+            ConfigError::Missing { key, env } => write!(f, "set {} in {} or {} in .env", key, CONFIG_FILE, env),
+            ConfigError::InvalidAddress(address) => write!(f, "contract address \"{}\" is not a 20-byte hex address", address),
+            ConfigError::InvalidRpcUrl(url) => write!(f, "RPC url \"{}\" is not a valid http(s) url", url),
+            #[cfg(feature = "dev-keys")]
+            ConfigError::InvalidPrivateKey => write!(f, "{} is not a 32-byte hex private key", PRIVATE_KEY),
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+/// Where the game plays on chain and where the player's keystore lives:
+/// `Stylus.toml` with environment overrides, checked before anything connects.
+#[derive(Resource, Clone)]
+pub struct ChainConfig {
+    pub address: Address,
+    pub rpc_url: String,
+    pub network: Option<String>,
+    pub keystore: PathBuf,
+    /// `PRIVATE_KEY` from `.env`, taken by the wallet prompt at startup.
+    #[cfg(feature = "dev-keys")]
+    pub dev_wallet: Option<LocalWallet>,
+}
+
+/// A non-empty environment variable.
+fn env(name: &str) -> Option<String> {
+    std::env::var(name).ok().filter(|value| !value.trim().is_empty())
+}
+
+fn config_path() -> PathBuf {
+    let local = Path::new(CONFIG_FILE);
+    if local.exists() {
+        return local.to_path_buf();
+    }
+    Path::new(env!("CARGO_MANIFEST_DIR")).join(CONFIG_FILE)
+}
+
+impl ChainConfig {
+    pub fn load() -> Result<Self, ConfigError> {
+        dotenv::dotenv().ok();
+        let path = config_path();
+        let file = match std::fs::read_to_string(&path) {
+            Ok(text) => toml::from_str(&text).map_err(|err| ConfigError::Parse(path.clone(), err))?,
+            // Everything can come from the environment instead.
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => StylusToml::default(),
+            Err(err) => return Err(ConfigError::Read(path, err)),
+        };
+
+        let address = env(STYLUS_CONTRACT_ADDRESS)
+            .or(file.contract.address)
+            .ok_or(ConfigError::Missing { key: "contract.address", env: STYLUS_CONTRACT_ADDRESS })?;
+        let address = address.trim().parse().map_err(|_| ConfigError::InvalidAddress(address))?;
+        let rpc_url = env(RPC_URL)
+            .or(file.contract.rpc_url)
+            .ok_or(ConfigError::Missing { key: "contract.rpc_url", env: RPC_URL })?;
+        Provider::<Http>::try_from(rpc_url.as_str()).map_err(|_| ConfigError::InvalidRpcUrl(rpc_url.clone()))?;
+        let keystore = env(KEYSTORE_PATH).map(PathBuf::from).unwrap_or_else(|| data_dir().join(KEYSTORE_FILE));
+        #[cfg(feature = "dev-keys")]
+        let dev_wallet = match env(PRIVATE_KEY).map(Zeroizing::new) {
+            Some(key) => Some(key.trim().parse().map_err(|_| ConfigError::InvalidPrivateKey)?),
+            None => None,
+        };
+        #[cfg(not(feature = "dev-keys"))]
+        if env(PRIVATE_KEY).is_some() {
+            warn!("Ignoring {} from the environment; build with --features dev-keys to use it", PRIVATE_KEY);
+        }
+        // Nothing else should find a plaintext key lying around.
+        std::env::remove_var(PRIVATE_KEY);
+        Ok(ChainConfig {
+            address,
+            rpc_url,
+            network: file.contract.network,
+            keystore,
+            #[cfg(feature = "dev-keys")]
+            dev_wallet,
+        })
+    }
+}
@@ -0,0 +1,42 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use bevy::prelude::*;
+
+/// Mirrors the contract's `DAILY_COOLDOWN`.
+const DAILY_COOLDOWN_SECS: u64 = 24 * 60 * 60;
+
+/// When the local player may next call `claimDaily`, as a unix timestamp.
+/// Seeded from `nextDailyClaim` on startup; 0 means a claim is available now.
+#[derive(Resource, Default)]
+pub struct DailyReward {
+    pub next_claim: u64,
+}
+
+impl DailyReward {
+    /// Seconds until the next claim, or `None` if one is available now.
+    pub fn remaining(&self) -> Option<u64> {
+        Some(self.next_claim.saturating_sub(now())).filter(|&remaining| remaining > 0)
+    }
+
+    /// Starts the cooldown locally so the button is disabled before the
+    /// claim transaction is mined.
+    pub fn mark_claimed(&mut self) {
+        self.next_claim = now() + DAILY_COOLDOWN_SECS;
+    }
+
+    pub fn describe(&self) -> String {
+        match self.remaining() {
+            Some(remaining) => format!(
+                "Next daily reward in {}h {:02}m {:02}s",
+                remaining / 3600,
+                remaining % 3600 / 60,
+                remaining % 60
+            ),
+            None => "Daily reward ready to claim".to_string(),
+        }
+    }
+}
+
+fn now() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|elapsed| elapsed.as_secs()).unwrap_or_default()
+}
@@ -0,0 +1,460 @@
+use std::collections::HashMap;
+use std::sync::mpsc::Sender;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use bevy::prelude::*;
+use bevy_stylus_plugin::StylusContract;
+use ethers::middleware::SignerMiddleware;
+use ethers::providers::{Http, Middleware, MiddlewareError, Provider};
+use ethers::signers::{LocalWallet, Signer};
+use ethers::types::transaction::eip2718::TypedTransaction;
+use ethers::types::{Address, BlockNumber, Bytes, Filter, TransactionReceipt, H256, U256, U64};
+use ethers::utils::keccak256;
+
+use crate::attest::{AttestClient, Attestation};
+use crate::backend::{BackendFuture, ChainBackend, ChainCall, PlayerSnapshot, SendError, SwordFound, TxCheck};
+use crate::claims::PickupSigner;
+use crate::compat;
+use crate::menu::GuildStatus;
+use crate::relay::RelayClient;
+use crate::revert;
+use crate::settlement;
+use crate::tournament::TournamentInfo;
+use crate::SWORD_COLORS;
+
+type Client = SignerMiddleware<Provider<Http>, LocalWallet>;
+
+/// Fee increase for a re-sent transaction. Nodes want at least 10% more to
+/// replace one, so this leaves room for rounding and a rising base fee.
+const FEE_BUMP_PERCENT: u64 = 20;
+/// Guilds below this place show no rank in the HUD.
+const GUILD_LEADERBOARD_SIZE: u64 = 100;
+const LOG_POLL: Duration = Duration::from_secs(5);
+/// Most blocks asked for in one `eth_getLogs`; public RPCs reject wide ranges.
+const MAX_BLOCK_RANGE: u64 = 2_000;
+const SWORD_FOUND_EVENT: &str = "SwordFound(address,uint256,uint256)";
+
+struct SentTx {
+    tx: TypedTransaction,
+    relayed: bool,
+}
+
+/// The sword contract on a real node, as the unlocked wallet. Sends through
+/// the relayer when one is configured and the call carries no value,
+/// otherwise from the player's own account with a locally tracked nonce.
+#[derive(Clone)]
+pub struct EthersBackend {
+    contract: StylusContract<Client>,
+    signer: PickupSigner,
+    relay: Option<RelayClient>,
+    attesters: Option<AttestClient>,
+    /// Next account nonce for direct sends; refetched after any send error.
+    nonce: Arc<Mutex<Option<U256>>>,
+    /// Transactions by hash, kept until mined for fee bumps and revert reasons.
+    sent: Arc<Mutex<HashMap<H256, SentTx>>>,
+}
+
+impl EthersBackend {
+    pub async fn connect(address: Address, rpc_url: &str, wallet: LocalWallet) -> Result<Self, String> {
+        let provider = Provider::<Http>::try_from(rpc_url).map_err(|err| err.to_string())?;
+        let chain_id = provider
+            .get_chainid()
+            .await
+            .map_err(|err| format!("Could not reach {}: {}", rpc_url, err))?
+            .as_u64();
+        let wallet = wallet.with_chain_id(chain_id);
+        let contract = StylusContract::new(address, Arc::new(SignerMiddleware::new(provider, wallet.clone())));
+        Ok(EthersBackend {
+            contract,
+            signer: PickupSigner::new(wallet.clone(), address),
+            relay: RelayClient::from_env(wallet),
+            attesters: AttestClient::from_env(),
+            nonce: Arc::default(),
+            sent: Arc::default(),
+        })
+    }
+
+    async fn compatible(&self) -> Result<(), String> {
+        let contract = &self.contract;
+        // Make sure the configured address points at a contract this build can talk to.
+        let version = contract
+            .version()
+            .call()
+            .await
+            .map_err(|_| format!("{:?} does not answer version(); is it a sword contract?", contract.address()))?;
+        compat::check_version(&version)?;
+        for (feature, id) in compat::required_interfaces() {
+            if !contract.supports_interface(id).call().await.unwrap_or(false) {
+                return Err(format!("Contract at {:?} does not support {}", contract.address(), feature));
+            }
+        }
+        Ok(())
+    }
+
+    async fn load(&self) -> PlayerSnapshot {
+        let contract = &self.contract;
+        let player = self.player();
+        let name = contract.name_of(player).call().await.ok().filter(|name| !name.is_empty());
+        let guild_id = contract.guild_of(player).call().await.unwrap_or_default();
+        let mut guild = None;
+        if !guild_id.is_zero() {
+            if let Ok((tag, _members, total)) = contract.get_guild(guild_id).call().await {
+                let leaderboard = contract.get_guild_leaderboard(GUILD_LEADERBOARD_SIZE.into()).call().await;
+                let rank = leaderboard.ok().and_then(|(ids, _totals)| ids.iter().position(|&id| id == guild_id)).map(|index| index + 1);
+                guild = Some(GuildStatus { tag, total_swords: total.low_u64(), rank });
+            }
+        }
+        // Claim swords the game server settled in a batch since the last session.
+        let mut settlement_claim = None;
+        if let Ok(root) = contract.settlement_root().call().await {
+            let settled = contract.get_settled_sword_counts(player).call().await.unwrap_or_default();
+            if let Ok(Some(claim)) = settlement::load_claim(player, root) {
+                if settlement::has_unclaimed(&claim, [settled.0, settled.1, settled.2, settled.3]) {
+                    settlement_claim = Some(ChainCall::ClaimSettlement { proof: claim.proof, counts: claim.result.color_counts });
+                }
+            }
+        }
+        PlayerSnapshot {
+            swords: contract.get_player_sword_counts(player).call().await.ok().map(to_counts),
+            loot_weights: contract.get_loot_table().call().await.ok().map(to_counts),
+            name,
+            guild,
+            kills: contract.kills_of(player).call().await.ok().map(|kills| kills.low_u64()),
+            equipped_skin: contract.equipped_skin(player).call().await.ok().map(|skin| skin.low_u64()),
+            next_daily_claim: contract.next_daily_claim(player).call().await.ok().map(|next| next.low_u64()),
+            settlement: settlement_claim,
+        }
+    }
+
+    async fn open_tournaments(&self) -> Result<Vec<TournamentInfo>, String> {
+        let contract = &self.contract;
+        let count = contract.tournament_count().call().await.map_err(|err| err.to_string())?.as_u64();
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).map(|now| now.as_secs()).unwrap_or_default();
+        let mut tournaments = Vec::new();
+        for id in 1..=count {
+            let Ok((entry_fee, start_time, end_time, prize_pool, entrants, _)) = contract.get_tournament(id.into()).call().await else {
+                continue;
+            };
+            if end_time.as_u64() > now {
+                tournaments.push(TournamentInfo {
+                    id,
+                    entry_fee,
+                    start_time: start_time.as_u64(),
+                    end_time: end_time.as_u64(),
+                    prize_pool,
+                    entrants: entrants.as_u64(),
+                });
+            }
+        }
+        Ok(tournaments)
+    }
+
+    /// The contract's next claim nonce. Read fresh for every claim, since the
+    /// previous transaction may have used one or given up.
+    async fn claim_nonce(&self) -> Result<U256, SendError> {
+        self.contract.nonces(self.player()).call().await.map_err(|err| SendError::Failed(err.to_string()))
+    }
+
+    /// The signatures a claim needs: the player's own while the contract has
+    /// no signer threshold, otherwise that many from signer services.
+    async fn claim_signatures(&self, own: Bytes, attestation: Attestation<'_>) -> Result<Bytes, SendError> {
+        let threshold = self.contract.signer_threshold().call().await.map_err(|err| SendError::Failed(err.to_string()))?;
+        if threshold.is_zero() {
+            return Ok(own);
+        }
+        // Kept as a send failure rather than a revert, so journaled pickups wait for the signers.
+        let Some(attesters) = &self.attesters else {
+            return Err(SendError::Failed(format!("the contract needs {} attestations; set ATTESTATION_URLS", threshold)));
+        };
+        let signatures = attesters
+            .attest(&attestation, threshold.low_u64() as usize)
+            .await
+            .map_err(|err| SendError::Failed(format!("could not collect attestations: {}", err)))?;
+        Ok(signatures.concat().into())
+    }
+
+    async fn transaction(&self, call: ChainCall) -> Result<TypedTransaction, SendError> {
+        let contract = &self.contract;
+        let signing_failed = |err: eyre::Report| SendError::Failed(format!("could not sign claim: {}", err));
+        Ok(match call {
+            ChainCall::IncrementSword { color } => {
+                let (claim, signature) = self.signer.sign(color, 1, self.claim_nonce().await?).map_err(signing_failed)?;
+                let signatures = self.claim_signatures(signature, Attestation::Pickup(&claim)).await?;
+                contract.claim_pickup(claim.player, claim.color, claim.amount, claim.nonce, claim.deadline, signatures).tx
+            }
+            ChainCall::ClaimKills { count } => {
+                let (claim, signature) = self.signer.sign_kills(count, self.claim_nonce().await?).map_err(signing_failed)?;
+                let signatures = self.claim_signatures(signature, Attestation::Kills(&claim)).await?;
+                contract.claim_kills(claim.player, claim.count, claim.nonce, claim.deadline, signatures).tx
+            }
+            ChainCall::TransferSwords { to, color, amount } => contract.transfer_swords(to, color.into(), amount.into()).tx,
+            ChainCall::SetName(name) => contract.set_name(name).tx,
+            ChainCall::ClaimDaily => contract.claim_daily().tx,
+            ChainCall::ClaimSettlement { proof, counts } => contract.claim(proof, counts.map(U256::from)).tx,
+            ChainCall::JoinTournament { id, entry_fee } => contract.join_tournament(id.into()).value(entry_fee).tx,
+        })
+    }
+
+    async fn send(&self, call: ChainCall) -> Result<H256, SendError> {
+        let mut tx = self.transaction(call).await?;
+        if let Some(relay) = self.relay.as_ref().filter(|_| tx.value().is_none_or(U256::is_zero)) {
+            let data = tx.data().cloned().ok_or_else(|| SendError::Failed("call has no calldata".to_string()))?;
+            let tx_hash = relay.send(data).await.map_err(|err| SendError::Failed(err.to_string()))?;
+            self.sent.lock().unwrap().insert(tx_hash, SentTx { tx, relayed: true });
+            return Ok(tx_hash);
+        }
+        let client = self.contract.client();
+        let cached = *self.nonce.lock().unwrap();
+        let nonce = match cached {
+            Some(nonce) => Ok(nonce),
+            None => client.get_transaction_count(client.address(), Some(BlockNumber::Pending.into())).await,
+        };
+        let sent = match nonce {
+            Ok(nonce) => {
+                tx.set_nonce(nonce);
+                match client.fill_transaction(&mut tx, None).await {
+                    Ok(()) => client.send_transaction(tx.clone(), None).await.map(|pending| (pending.tx_hash(), nonce)),
+                    Err(err) => Err(err),
+                }
+            }
+            Err(err) => Err(err),
+        };
+        match sent {
+            Ok((tx_hash, nonce)) => {
+                *self.nonce.lock().unwrap() = Some(nonce + 1);
+                self.sent.lock().unwrap().insert(tx_hash, SentTx { tx, relayed: false });
+                Ok(tx_hash)
+            }
+            Err(err) => {
+                let err = send_error(err);
+                if let SendError::Failed(_) = err {
+                    *self.nonce.lock().unwrap() = None;
+                }
+                Err(err)
+            }
+        }
+    }
+
+    async fn check_sent(&self, hashes: Vec<H256>, stuck: bool) -> Result<TxCheck, String> {
+        let client = self.contract.client();
+        // A fee bump replaces the transaction, but the earlier one may still be the one that gets mined.
+        for tx_hash in hashes.iter().rev() {
+            let Some(receipt) = client.get_transaction_receipt(*tx_hash).await.map_err(|err| err.to_string())? else {
+                continue;
+            };
+            let mined = {
+                let mut sent = self.sent.lock().unwrap();
+                let mined = sent.remove(tx_hash);
+                for tx_hash in &hashes {
+                    sent.remove(tx_hash);
+                }
+                mined
+            };
+            if receipt.status == Some(1.into()) {
+                return Ok(TxCheck::Mined(receipt.transaction_hash, Ok(())));
+            }
+            let reason = match mined {
+                Some(mined) => replay_revert(&client, &mined.tx, &receipt).await,
+                None => "reverted".to_string(),
+            };
+            return Ok(TxCheck::Mined(receipt.transaction_hash, Err(reason)));
+        }
+
+        let resend = hashes.last().and_then(|latest| {
+            let sent = self.sent.lock().unwrap();
+            sent.get(latest).filter(|sent| stuck && !sent.relayed).map(|sent| sent.tx.clone())
+        });
+        let Some(mut tx) = resend else {
+            return Ok(TxCheck::Pending);
+        };
+        bump_fees(&mut tx);
+        let resent = client.send_transaction(tx.clone(), None).await.map(|pending| pending.tx_hash());
+        Ok(match resent {
+            Ok(tx_hash) => {
+                self.sent.lock().unwrap().insert(tx_hash, SentTx { tx, relayed: false });
+                TxCheck::Resent(tx_hash)
+            }
+            Err(err) => TxCheck::ResendFailed(err.to_string()),
+        })
+    }
+
+    /// Polls `SwordFound` logs from a block cursor. Falls back to diffing
+    /// `getSwordCounts` when the node refuses `eth_getLogs`, or when the
+    /// contract predates the event.
+    async fn watch(self, found: Sender<SwordFound>) {
+        let contract = &self.contract;
+        let client = contract.client();
+        let version = contract.version().call().await.unwrap_or_default();
+        let mut use_logs = compat::emits_sword_found(&version);
+        let mut cursor: Option<U64> = None;
+        let mut previous = None;
+        let mut names: HashMap<Address, String> = HashMap::new();
+        let mut interval = tokio::time::interval(LOG_POLL);
+        loop {
+            interval.tick().await;
+            let mut finds = Vec::new();
+            if use_logs {
+                match (client.get_block_number().await, cursor) {
+                    // Start at the head; the feed is for what happens while we watch.
+                    (Ok(latest), None) => cursor = Some(latest + 1),
+                    (Ok(latest), Some(from)) if from <= latest => {
+                        let to = latest.min(from + MAX_BLOCK_RANGE - 1);
+                        let filter = Filter::new()
+                            .address(contract.address())
+                            .topic0(H256(keccak256(SWORD_FOUND_EVENT)))
+                            .from_block(from)
+                            .to_block(to);
+                        match client.get_logs(&filter).await {
+                            Ok(logs) => {
+                                cursor = Some(to + 1);
+                                for log in logs.iter().filter(|log| log.topics.len() == 3) {
+                                    finds.push(SwordFound {
+                                        player: Some(Address::from(log.topics[1])),
+                                        name: None,
+                                        color: U256::from_big_endian(log.topics[2].as_bytes()).low_u64() as u8,
+                                        amount: U256::from_big_endian(&log.data).low_u64(),
+                                    });
+                                }
+                            }
+                            Err(err) => {
+                                warn!("eth_getLogs failed, watching sword counts instead: {}", err);
+                                use_logs = false;
+                            }
+                        }
+                    }
+                    (Ok(_), Some(_)) => {}
+                    (Err(err), _) => warn!("Could not read the block number: {}", err),
+                }
+            }
+            if !use_logs {
+                if let Ok(totals) = contract.get_sword_counts().call().await.map(to_counts) {
+                    for (color, (&before, &after)) in previous.unwrap_or(totals).iter().zip(totals.iter()).enumerate() {
+                        if after > before {
+                            finds.push(SwordFound { player: None, name: None, color: color as u8, amount: after - before });
+                        }
+                    }
+                    previous = Some(totals);
+                }
+            }
+            for mut find in finds {
+                if let Some(player) = find.player {
+                    find.name = match names.get(&player) {
+                        Some(name) => Some(name.clone()),
+                        None => match contract.name_of(player).call().await {
+                            Ok(name) if !name.is_empty() => {
+                                names.insert(player, name.clone());
+                                Some(name)
+                            }
+                            _ => None,
+                        },
+                    };
+                }
+                if found.send(find).is_err() {
+                    return;
+                }
+            }
+        }
+    }
+}
+
+impl ChainBackend for EthersBackend {
+    fn player(&self) -> Address {
+        self.contract.client().address()
+    }
+
+    fn check_compatible(&self) -> BackendFuture<Result<(), String>> {
+        let this = self.clone();
+        Box::pin(async move { this.compatible().await })
+    }
+
+    fn load_player(&self) -> BackendFuture<PlayerSnapshot> {
+        let this = self.clone();
+        Box::pin(async move { this.load().await })
+    }
+
+    fn get_sword_counts(&self) -> BackendFuture<Result<[u64; SWORD_COLORS], String>> {
+        let contract = self.contract.clone();
+        Box::pin(async move { contract.get_sword_counts().call().await.map(to_counts).map_err(|err| err.to_string()) })
+    }
+
+    fn get_player_sword_counts(&self) -> BackendFuture<Result<[u64; SWORD_COLORS], String>> {
+        let (contract, player) = (self.contract.clone(), self.player());
+        Box::pin(async move {
+            contract.get_player_sword_counts(player).call().await.map(to_counts).map_err(|err| err.to_string())
+        })
+    }
+
+    fn tournaments(&self) -> BackendFuture<Result<Vec<TournamentInfo>, String>> {
+        let this = self.clone();
+        Box::pin(async move { this.open_tournaments().await })
+    }
+
+    fn current_tournament(&self) -> BackendFuture<Result<u64, String>> {
+        let (contract, player) = (self.contract.clone(), self.player());
+        Box::pin(async move {
+            contract.current_tournament(player).call().await.map(|id| id.low_u64()).map_err(|err| err.to_string())
+        })
+    }
+
+    fn submit(&self, call: ChainCall) -> BackendFuture<Result<H256, SendError>> {
+        let this = self.clone();
+        Box::pin(async move { this.send(call).await })
+    }
+
+    fn check(&self, hashes: Vec<H256>, stuck: bool) -> BackendFuture<Result<TxCheck, String>> {
+        let this = self.clone();
+        Box::pin(async move { this.check_sent(hashes, stuck).await })
+    }
+
+    fn abandon(&self, hashes: Vec<H256>) {
+        let mut sent = self.sent.lock().unwrap();
+        for tx_hash in &hashes {
+            sent.remove(tx_hash);
+        }
+        // A dropped direct send leaves a gap at its nonce; refetch it.
+        *self.nonce.lock().unwrap() = None;
+    }
+
+    fn subscribe(&self, found: Sender<SwordFound>) -> BackendFuture<()> {
+        Box::pin(self.clone().watch(found))
+    }
+}
+
+fn to_counts((red, green, blue, normal): (U256, U256, U256, U256)) -> [u64; SWORD_COLORS] {
+    [red.low_u64(), green.low_u64(), blue.low_u64(), normal.low_u64()]
+}
+
+fn send_error<E: MiddlewareError>(err: E) -> SendError {
+    match revert::reason(&err) {
+        Some(reason) => SendError::Reverted(reason),
+        // Some nodes report the revert without its data.
+        None if err.to_string().contains("execution reverted") => SendError::Reverted(err.to_string()),
+        None => SendError::Failed(err.to_string()),
+    }
+}
+
+fn bump_fees(tx: &mut TypedTransaction) {
+    let bump = |fee: U256| fee * (100 + FEE_BUMP_PERCENT) / 100;
+    match tx {
+        TypedTransaction::Eip1559(request) => {
+            request.max_fee_per_gas = request.max_fee_per_gas.map(bump);
+            request.max_priority_fee_per_gas = request.max_priority_fee_per_gas.map(bump);
+        }
+        _ => {
+            if let Some(gas_price) = tx.gas_price() {
+                tx.set_gas_price(bump(gas_price));
+            }
+        }
+    }
+}
+
+/// Re-runs a reverted transaction against the state just before its block to
+/// recover the revert data, which receipts do not carry.
+async fn replay_revert(client: &Client, tx: &TypedTransaction, receipt: &TransactionReceipt) -> String {
+    let block = receipt.block_number.map(|number| BlockNumber::Number(number.saturating_sub(1.into())).into());
+    match client.call(tx, block).await {
+        Err(err) => revert::reason(&err).unwrap_or_else(|| err.to_string()),
+        Ok(_) => "reverted".to_string(),
+    }
+}
@@ -0,0 +1,203 @@
+use bevy::input::keyboard::{Key, KeyboardInput};
+use bevy::input::ButtonState;
+use bevy::prelude::*;
+use ethers::types::Address;
+
+use crate::backend::ChainCall;
+use crate::menu::AppState;
+use crate::tx_queue::ChainIo;
+use crate::{GameState, SWORD_COLORS};
+
+const COLOR_NAMES: [&str; SWORD_COLORS] = ["Red", "Green", "Blue", "Normal"];
+
+#[derive(Resource)]
+struct GiftDialog {
+    recipient: String,
+    color: u8,
+    amount: usize,
+    status: String,
+}
+
+impl Default for GiftDialog {
+    fn default() -> Self {
+        GiftDialog {
+            recipient: "0x".to_string(),
+            color: 0,
+            amount: 1,
+            status: String::new(),
+        }
+    }
+}
+
+#[derive(Component)]
+struct GiftDialogRoot;
+
+#[derive(Component)]
+struct GiftDialogText;
+
+pub struct GiftPlugin;
+
+impl Plugin for GiftPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<GiftDialog>()
+            .add_systems(Update, open_gift_dialog.run_if(in_state(AppState::InGame)))
+            .add_systems(OnEnter(AppState::Gifting), spawn_gift_dialog)
+            .add_systems(OnExit(AppState::Gifting), despawn_gift_dialog)
+            .add_systems(Update, (
+                gift_input,
+                update_gift_text,
+            ).chain().run_if(in_state(AppState::Gifting)));
+    }
+}
+
+fn open_gift_dialog(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    mut next_state: ResMut<NextState<AppState>>,
+) {
+    if keyboard.just_pressed(KeyCode::KeyG) {
+        next_state.set(AppState::Gifting);
+    }
+}
+
+fn spawn_gift_dialog(mut commands: Commands, mut dialog: ResMut<GiftDialog>) {
+    dialog.status = String::new();
+
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    align_items: AlignItems::Center,
+                    justify_content: JustifyContent::Center,
+                    ..default()
+                },
+                background_color: Color::srgba(0.0, 0.0, 0.0, 0.6).into(),
+                z_index: ZIndex::Global(10),
+                ..default()
+            },
+            GiftDialogRoot,
+        ))
+        .with_children(|parent| {
+            parent
+                .spawn(NodeBundle {
+                    style: Style {
+                        padding: UiRect::all(Val::Px(20.0)),
+                        ..default()
+                    },
+                    background_color: Color::srgb(0.1, 0.1, 0.2).into(),
+                    ..default()
+                })
+                .with_children(|parent| {
+                    parent.spawn((
+                        TextBundle::from_section(
+                            "",
+                            TextStyle { font_size: 22.0, color: Color::WHITE, ..default() },
+                        ),
+                        GiftDialogText,
+                    ));
+                });
+        });
+}
+
+fn despawn_gift_dialog(mut commands: Commands, dialog_query: Query<Entity, With<GiftDialogRoot>>) {
+    for entity in dialog_query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+fn gift_input(
+    mut keyboard_events: EventReader<KeyboardInput>,
+    mut dialog: ResMut<GiftDialog>,
+    mut game_state: ResMut<GameState>,
+    mut next_state: ResMut<NextState<AppState>>,
+    mut io: ChainIo,
+) {
+    for event in keyboard_events.read() {
+        if event.state != ButtonState::Pressed {
+            continue;
+        }
+        match &event.logical_key {
+            Key::Escape => next_state.set(AppState::InGame),
+            Key::Tab => dialog.color = (dialog.color + 1) % SWORD_COLORS as u8,
+            Key::ArrowUp => dialog.amount += 1,
+            Key::ArrowDown => dialog.amount = dialog.amount.saturating_sub(1).max(1),
+            Key::Backspace => {
+                if dialog.recipient.len() > 2 {
+                    dialog.recipient.pop();
+                }
+            }
+            Key::Character(characters) => {
+                for character in characters.chars() {
+                    if character.is_ascii_hexdigit() && dialog.recipient.len() < 42 {
+                        dialog.recipient.push(character.to_ascii_lowercase());
+                    }
+                }
+            }
+            Key::Enter => {
+                let recipient = match dialog.recipient.parse::<Address>() {
+                    Ok(recipient) => recipient,
+                    Err(_) => {
+                        dialog.status = "Enter a full 0x address".to_string();
+                        continue;
+                    }
+                };
+                // Without a backend nothing would send the transfer or put the swords back.
+                let Some(backend) = &io.backend else {
+                    dialog.status = "Not connected".to_string();
+                    continue;
+                };
+                if recipient.is_zero() || recipient == backend.player() {
+                    dialog.status = "Enter another player's address".to_string();
+                    continue;
+                }
+                let (color, amount) = (dialog.color, dialog.amount);
+                let owned = game_state.swords_collected.iter().filter(|&&c| c == color).count();
+                if owned < amount {
+                    dialog.status = format!("You only have {} {} swords", owned, COLOR_NAMES[color as usize]);
+                    continue;
+                }
+
+                let mut remaining = amount;
+                game_state.swords_collected.retain(|&c| {
+                    if c == color && remaining > 0 {
+                        remaining -= 1;
+                        false
+                    } else {
+                        true
+                    }
+                });
+                info!("Gifting {} {} swords to {:?}", amount, COLOR_NAMES[color as usize], recipient);
+
+                let call = ChainCall::TransferSwords { to: recipient, color, amount: amount as u64 };
+                let tx = io.tx_queue.push("gift", call);
+                io.inventory.record(tx, color, -(amount as i64));
+
+                next_state.set(AppState::InGame);
+            }
+            _ => {}
+        }
+    }
+}
+
+fn update_gift_text(
+    dialog: Res<GiftDialog>,
+    game_state: Res<GameState>,
+    mut text_query: Query<&mut Text, With<GiftDialogText>>,
+) {
+    if !dialog.is_changed() {
+        return;
+    }
+    let owned = game_state.swords_collected.iter().filter(|&&c| c == dialog.color).count();
+    let display_text = format!(
+        "Gift swords\n\nRecipient: {}_\nColor: {} (you have {})\nAmount: {}\n\n{}\n\nTab: color  Up/Down: amount  Enter: send  Esc: cancel",
+        dialog.recipient,
+        COLOR_NAMES[dialog.color as usize],
+        owned,
+        dialog.amount,
+        dialog.status,
+    );
+    for mut text in text_query.iter_mut() {
+        text.sections[0].value = display_text.clone();
+    }
+}
@@ -0,0 +1,221 @@
+use bevy::prelude::*;
+
+use crate::backend::Backend;
+use crate::journal::PickupJournal;
+use crate::runtime::ChainRuntime;
+use crate::tx_queue::{TxId, TxQueue, TxStatus};
+use crate::{GameState, SWORD_COLORS};
+
+/// How often local sword counts are checked against the contract.
+const RECONCILE_SECS: f32 = 30.0;
+
+/// A change to `GameState::swords_collected` made before its transaction is
+/// mined: one sword for a pickup, minus the amount for a gift.
+struct PendingChange {
+    tx: TxId,
+    color: u8,
+    delta: i64,
+}
+
+/// Optimistic inventory bookkeeping. Swords show up as soon as they are picked
+/// up; a failed transaction takes them back out, and the contract's counts win
+/// whenever they disagree with what is shown, e.g. after play on another machine.
+/// Pickups that could not be sent stay shown while the `PickupJournal` holds them.
+#[derive(Resource)]
+pub struct Inventory {
+    pending: Vec<PendingChange>,
+    /// Bumped whenever a change is confirmed or rolled back, so a chain read
+    /// started before then is not mistaken for the truth.
+    generation: u64,
+    reading: bool,
+    timer: Timer,
+}
+
+pub struct InventoryPlugin;
+
+impl Plugin for InventoryPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(Inventory {
+            pending: Vec::new(),
+            generation: 0,
+            reading: false,
+            timer: Timer::from_seconds(RECONCILE_SECS, TimerMode::Repeating),
+        })
+        .add_systems(Update, (settle_changes, reconcile_inventory).chain());
+    }
+}
+
+impl Inventory {
+    pub fn record(&mut self, tx: TxId, color: u8, delta: i64) {
+        self.pending.push(PendingChange { tx, color, delta });
+    }
+
+    pub fn pending(&self) -> usize {
+        self.pending.len()
+    }
+
+    /// What the contract should report once every pending change lands.
+    fn expected(&self, mut counts: [u64; SWORD_COLORS]) -> [u64; SWORD_COLORS] {
+        for change in &self.pending {
+            let count = &mut counts[change.color as usize];
+            *count = count.saturating_add_signed(change.delta);
+        }
+        counts
+    }
+}
+
+fn undo(game_state: &mut GameState, change: &PendingChange) {
+    if change.delta < 0 {
+        let returned = std::iter::repeat_n(change.color, change.delta.unsigned_abs() as usize);
+        game_state.swords_collected.extend(returned);
+        return;
+    }
+    for _ in 0..change.delta {
+        if let Some(index) = game_state.swords_collected.iter().rposition(|&color| color == change.color) {
+            game_state.swords_collected.remove(index);
+        }
+    }
+}
+
+fn settle_changes(mut inventory: ResMut<Inventory>, tx_queue: Res<TxQueue>, mut game_state: ResMut<GameState>) {
+    if !tx_queue.is_changed() {
+        return;
+    }
+    let inventory = &mut *inventory;
+    let before = inventory.pending.len();
+    inventory.pending.retain(|change| match tx_queue.get(change.tx).map(|entry| &entry.status) {
+        Some(TxStatus::Confirmed(_)) => false,
+        Some(TxStatus::Failed(reason)) => {
+            warn!("Rolling back sword change after failed transaction: {}", reason);
+            undo(&mut game_state, change);
+            false
+        }
+        // The journal keeps the pickup and sends it again once the node is back.
+        Some(TxStatus::Unsent(_)) if change.delta > 0 => false,
+        Some(TxStatus::Unsent(reason)) => {
+            warn!("Rolling back sword change that could not be sent: {}", reason);
+            undo(&mut game_state, change);
+            false
+        }
+        Some(_) => true,
+        // Pruned before we saw it finish; the next reconcile settles the count.
+        None => false,
+    });
+    if inventory.pending.len() != before {
+        inventory.generation += 1;
+    }
+}
+
+fn reconcile_inventory(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut inventory: ResMut<Inventory>,
+    backend: Option<Res<Backend>>,
+    chain: Res<ChainRuntime>,
+) {
+    if !inventory.timer.tick(time.delta()).just_finished() || inventory.reading {
+        return;
+    }
+    let Some(backend) = backend else {
+        return;
+    };
+    inventory.reading = true;
+    let generation = inventory.generation;
+    let reading = backend.get_player_sword_counts();
+    chain.spawn(&mut commands, async move {
+        let counts = reading.await;
+        move |world: &mut World| {
+            let mut inventory = world.resource_mut::<Inventory>();
+            inventory.reading = false;
+            let counts = match counts {
+                Ok(counts) => counts,
+                Err(err) => return warn!("Could not read sword counts: {}", err),
+            };
+            if inventory.generation != generation {
+                return;
+            }
+            let mut expected = inventory.expected(counts);
+            for (count, unsynced) in expected.iter_mut().zip(world.resource::<PickupJournal>().unsynced_counts()) {
+                *count += unsynced;
+            }
+            let mut game_state = world.resource_mut::<GameState>();
+            if game_state.sword_counts() != expected {
+                info!("Adopting on-chain sword counts {:?}", expected);
+                game_state.set_sword_counts(expected);
+            }
+        }
+    });
+}
+
+#[cfg(all(test, not(feature = "chain")))]
+mod tests {
+    use ethers::types::Address;
+
+    use super::*;
+    use crate::backend::ChainCall;
+    use crate::mock_backend::{MockBackend, MockConfig};
+    use crate::test_app;
+
+    /// Takes `amount` swords of `color` out of `GameState` and queues the
+    /// transfer, the way the gift dialog does.
+    fn gift(world: &mut World, color: u8, amount: u64) -> TxId {
+        let mut game_state = world.resource_mut::<GameState>();
+        let mut counts = game_state.sword_counts();
+        counts[color as usize] -= amount;
+        game_state.set_sword_counts(counts);
+        let call = ChainCall::TransferSwords { to: Address::repeat_byte(0x02), color, amount };
+        let tx = world.resource_mut::<TxQueue>().push("gift", call);
+        world.resource_mut::<Inventory>().record(tx, color, -(amount as i64));
+        tx
+    }
+
+    fn settled(world: &World, tx: TxId) -> bool {
+        world.resource::<TxQueue>().get(tx).is_some_and(|entry| entry.status.is_finished())
+            && world.resource::<Inventory>().pending() == 0
+    }
+
+    #[test]
+    fn reverted_gift_returns_the_swords() {
+        let backend = MockBackend::new(MockConfig::default());
+        let mut app = test_app::app();
+        test_app::connect(&mut app, &backend);
+        // Shown locally but never on chain, so the transfer reverts.
+        app.world_mut().resource_mut::<GameState>().set_sword_counts([2, 0, 0, 0]);
+        let tx = gift(app.world_mut(), 0, 2);
+        assert_eq!(app.world().resource::<GameState>().sword_counts(), [0; SWORD_COLORS]);
+
+        test_app::run_until(&mut app, |world| settled(world, tx));
+        assert!(matches!(app.world().resource::<TxQueue>().get(tx).unwrap().status, TxStatus::Failed(_)));
+        assert_eq!(app.world().resource::<GameState>().sword_counts(), [2, 0, 0, 0]);
+    }
+
+    #[test]
+    fn unsent_gift_is_rolled_back_but_an_unsent_pickup_stays() {
+        let backend = MockBackend::new(MockConfig { failure_rate: 1.0, ..default() });
+        let mut app = test_app::app();
+        test_app::connect(&mut app, &backend);
+        app.world_mut().resource_mut::<GameState>().set_sword_counts([0, 1, 0, 0]);
+        let world = app.world_mut();
+        let pickup = world.resource_mut::<TxQueue>().push("pickup claim", ChainCall::IncrementSword { color: 2 });
+        world.resource_mut::<Inventory>().record(pickup, 2, 1);
+        world.resource_mut::<GameState>().swords_collected.push(2);
+        let tx = gift(world, 1, 1);
+
+        test_app::run_until(&mut app, |world| settled(world, pickup) && settled(world, tx));
+        assert_eq!(app.world().resource::<GameState>().sword_counts(), [0, 1, 1, 0]);
+    }
+
+    #[test]
+    fn confirmed_changes_stay() {
+        let backend = MockBackend::new(MockConfig::default());
+        let mut app = test_app::app();
+        test_app::connect(&mut app, &backend);
+        let world = app.world_mut();
+        let tx = world.resource_mut::<TxQueue>().push("pickup claim", ChainCall::IncrementSword { color: 3 });
+        world.resource_mut::<Inventory>().record(tx, 3, 1);
+        world.resource_mut::<GameState>().swords_collected.push(3);
+
+        test_app::run_until(&mut app, |world| settled(world, tx));
+        assert_eq!(app.world().resource::<GameState>().sword_counts(), [0, 0, 0, 1]);
+    }
+}
@@ -0,0 +1,490 @@
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::time::{SystemTime, UNIX_EPOCH};
+use std::path::{Path, PathBuf};
+
+use bevy::prelude::*;
+use ethers::types::{Address, H256};
+use serde::{Deserialize, Serialize};
+
+use crate::backend::{Backend, ChainCall, TxCheck};
+use crate::runtime::ChainRuntime;
+use crate::tx_queue::{ChainIo, TxId, TxQueue, TxStatus};
+use crate::{data_dir, GameState, SWORD_COLORS};
+#[cfg(all(test, not(feature = "chain")))]
+use crate::inventory::Inventory;
+
+/// How often the node is tried while pickups wait for it.
+const PROBE_SECS: f32 = 10.0;
+
+/// One line of the journal file. Lines are only ever appended; the file is
+/// rewritten without settled pickups when it is opened.
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+enum JournalLine {
+    Picked { pickup: u64, color: u8 },
+    /// A transaction for the pickup went out in `tx`.
+    Sent { pickup: u64, tx: H256 },
+    /// Confirmed, or reverted and rolled back.
+    Settled { pickup: u64 },
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum PickupState {
+    /// Waiting for the node to answer again.
+    Parked,
+    /// Looking for receipts of transactions sent in an earlier session.
+    Checking,
+    Queued(TxId),
+}
+
+struct Pickup {
+    id: u64,
+    color: u8,
+    hashes: Vec<H256>,
+    /// Whether the sword is in `GameState`. Pickups carried over from an
+    /// earlier session are added once they are known not to be on chain yet.
+    shown: bool,
+    state: PickupState,
+}
+
+/// Every pickup that is not settled on chain yet, mirrored to a JSONL file
+/// under the data dir so pickups made while the node is unreachable survive
+/// a restart. Pickups start out parked and are sent once there is a
+/// `Backend` and the node answers.
+#[derive(Resource)]
+pub struct PickupJournal {
+    path: Option<PathBuf>,
+    pickups: Vec<Pickup>,
+    next_id: u64,
+    /// The node answered since pickups were last parked. Set whenever a new
+    /// `Backend` arrives.
+    reachable: bool,
+    probing: bool,
+    timer: Timer,
+}
+
+pub struct JournalPlugin;
+
+impl Plugin for JournalPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(PickupJournal {
+            path: None,
+            pickups: Vec::new(),
+            next_id: 0,
+            reachable: false,
+            probing: false,
+            timer: Timer::from_seconds(PROBE_SECS, TimerMode::Repeating),
+        })
+        .add_systems(Update, (
+            resume_pickups.run_if(resource_added::<Backend>),
+            track_pickups,
+            replay_pickups,
+        ).chain());
+    }
+}
+
+impl PickupJournal {
+    /// Journals a sword the player just picked up; `replay_pickups` claims it.
+    pub fn record(&mut self, color: u8) {
+        let id = self.next_id;
+        self.next_id += 1;
+        self.append(&JournalLine::Picked { pickup: id, color });
+        self.pickups.push(Pickup { id, color, hashes: Vec::new(), shown: true, state: PickupState::Parked });
+    }
+
+    /// Loads `player`'s journal file. Pickups recorded before it opened are
+    /// kept and written to it.
+    pub fn open(&mut self, player: Address) {
+        self.open_file(data_dir().join(format!("pickups-{:?}.jsonl", player)));
+    }
+
+    fn open_file(&mut self, path: PathBuf) {
+        let pickups = match load(&path) {
+            Ok(pickups) => pickups,
+            // Compacting now would drop whatever the file holds, so keep it
+            // under another name and start a new one.
+            Err(err) => {
+                let aside = set_aside_path(&path);
+                warn!("Could not read pickup journal {}: {}; moving it to {}", path.display(), err, aside.display());
+                if let Err(err) = fs::rename(&path, &aside) {
+                    warn!("Could not move pickup journal {}: {}; pickups are kept in memory only", path.display(), err);
+                    return;
+                }
+                Vec::new()
+            }
+        };
+        if !pickups.is_empty() {
+            info!("Replaying {} unsynced pickups from {}", pickups.len(), path.display());
+        }
+
+        // Pickups made before the journal opened were never written and take ids after the loaded ones.
+        let offset = pickups.iter().map(|pickup| pickup.id + 1).max().unwrap_or_default();
+        for pickup in &mut self.pickups {
+            pickup.id += offset;
+        }
+        self.next_id += offset;
+        self.pickups.splice(0..0, pickups);
+
+        // Start the file over with just what is still unsettled.
+        let mut lines = Vec::new();
+        for pickup in &self.pickups {
+            lines.push(JournalLine::Picked { pickup: pickup.id, color: pickup.color });
+            lines.extend(pickup.hashes.iter().map(|&tx| JournalLine::Sent { pickup: pickup.id, tx }));
+        }
+        let compacted = lines
+            .iter()
+            .map(|line| serde_json::to_string(line).map(|json| json + "\n"))
+            .collect::<Result<String, _>>()
+            .map_err(std::io::Error::from)
+            .and_then(|text| {
+                if let Some(dir) = path.parent() {
+                    fs::create_dir_all(dir)?;
+                }
+                fs::write(&path, text)
+            });
+        if let Err(err) = compacted {
+            warn!("Could not write pickup journal {}: {}", path.display(), err);
+        }
+        self.path = Some(path);
+    }
+
+    /// Pickups waiting for the node.
+    pub fn unsynced(&self) -> usize {
+        self.pickups.iter().filter(|pickup| !matches!(pickup.state, PickupState::Queued(_))).count()
+    }
+
+    /// Shown swords per color that are waiting for the node and are not in
+    /// `Inventory`'s pending changes.
+    pub fn unsynced_counts(&self) -> [u64; SWORD_COLORS] {
+        let mut counts = [0; SWORD_COLORS];
+        for pickup in &self.pickups {
+            if pickup.shown && !matches!(pickup.state, PickupState::Queued(_)) {
+                counts[pickup.color as usize] += 1;
+            }
+        }
+        counts
+    }
+
+    fn append(&self, line: &JournalLine) {
+        let Some(path) = &self.path else {
+            return;
+        };
+        let written = serde_json::to_string(line).map_err(std::io::Error::from).and_then(|json| {
+            let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+            writeln!(file, "{}", json)
+        });
+        if let Err(err) = written {
+            warn!("Could not write pickup journal {}: {}", path.display(), err);
+        }
+    }
+
+    fn settle(&mut self, id: u64) {
+        self.pickups.retain(|pickup| pickup.id != id);
+        self.append(&JournalLine::Settled { pickup: id });
+    }
+
+    /// Applies a receipt check for a carried-over pickup.
+    fn checked(&mut self, id: u64, check: Result<TxCheck, String>) {
+        self.reachable = true;
+        let Some(index) = self.pickups.iter().position(|pickup| pickup.id == id) else {
+            return;
+        };
+        let pickup = &mut self.pickups[index];
+        match check {
+            Ok(TxCheck::Mined(tx_hash, Ok(()))) => {
+                info!("Pickup {} was already confirmed in {:?}", id, tx_hash);
+                self.settle(id);
+            }
+            Ok(TxCheck::Mined(tx_hash, Err(reason))) => {
+                warn!("Dropping pickup {}, it reverted in {:?}: {}", id, tx_hash, reason);
+                self.settle(id);
+            }
+            // Never mined, so send it again. The claim nonce keeps a late
+            // receipt for the old transaction from counting twice.
+            Ok(_) => {
+                pickup.hashes.clear();
+                pickup.state = PickupState::Parked;
+            }
+            Err(err) => {
+                warn!("Could not check pickup {}: {}", id, err);
+                pickup.state = PickupState::Parked;
+                self.reachable = false;
+            }
+        }
+    }
+}
+
+/// Where an unreadable journal is kept, e.g. `pickups-0x…jsonl.corrupt-1700000000`.
+fn set_aside_path(path: &Path) -> PathBuf {
+    let secs = SystemTime::now().duration_since(UNIX_EPOCH).map(|now| now.as_secs()).unwrap_or_default();
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(format!(".corrupt-{}", secs));
+    path.with_file_name(name)
+}
+
+/// Reads the journal, keeping pickups that were never settled. Lines that do
+/// not parse are skipped.
+fn load(path: &Path) -> std::io::Result<Vec<Pickup>> {
+    let file = match File::open(path) {
+        Ok(file) => file,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(err) => return Err(err),
+    };
+    let mut pickups: Vec<Pickup> = Vec::new();
+    for line in BufReader::new(file).split(b'\n') {
+        let line = line?;
+        // A crash mid-write can leave a torn last line.
+        let Ok(line) = serde_json::from_slice::<JournalLine>(&line) else {
+            continue;
+        };
+        match line {
+            JournalLine::Picked { pickup, color } if (color as usize) < SWORD_COLORS => pickups.push(Pickup {
+                id: pickup,
+                color,
+                hashes: Vec::new(),
+                shown: false,
+                state: PickupState::Parked,
+            }),
+            JournalLine::Picked { .. } => {}
+            JournalLine::Sent { pickup, tx } => {
+                if let Some(pickup) = pickups.iter_mut().find(|entry| entry.id == pickup) {
+                    pickup.hashes.push(tx);
+                }
+            }
+            JournalLine::Settled { pickup } => pickups.retain(|entry| entry.id != pickup),
+        }
+    }
+    Ok(pickups)
+}
+
+/// A new session means the node just answered, so parked pickups go out
+/// without waiting for a probe.
+fn resume_pickups(mut journal: ResMut<PickupJournal>) {
+    journal.reachable = true;
+    journal.timer.reset();
+}
+
+/// Follows queued pickups through the transaction queue.
+fn track_pickups(mut journal: ResMut<PickupJournal>, tx_queue: Res<TxQueue>) {
+    if !tx_queue.is_changed() {
+        return;
+    }
+    let journal = &mut *journal;
+    let mut sent = Vec::new();
+    let mut settled = Vec::new();
+    for pickup in &mut journal.pickups {
+        let PickupState::Queued(tx) = pickup.state else {
+            continue;
+        };
+        match tx_queue.get(tx).map(|entry| &entry.status) {
+            Some(TxStatus::Pending(tx_hash)) if !pickup.hashes.contains(tx_hash) => {
+                pickup.hashes.push(*tx_hash);
+                sent.push(JournalLine::Sent { pickup: pickup.id, tx: *tx_hash });
+            }
+            Some(TxStatus::Confirmed(_) | TxStatus::Failed(_)) => settled.push(pickup.id),
+            Some(TxStatus::Unsent(_)) | None => {
+                pickup.state = PickupState::Parked;
+                journal.reachable = false;
+            }
+            Some(_) => {}
+        }
+    }
+    for line in &sent {
+        journal.append(line);
+    }
+    for id in settled {
+        journal.settle(id);
+    }
+}
+
+/// Probes the node while pickups are parked, and sends them again once it
+/// answers. Pickups already sent once are first looked up by receipt.
+fn replay_pickups(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut journal: ResMut<PickupJournal>,
+    chain: Res<ChainRuntime>,
+    mut io: ChainIo,
+    mut game_state: ResMut<GameState>,
+) {
+    let Some(backend) = io.backend.as_deref() else {
+        return;
+    };
+    if journal.probing || !journal.pickups.iter().any(|pickup| pickup.state == PickupState::Parked) {
+        return;
+    }
+    if !journal.reachable {
+        if !journal.timer.tick(time.delta()).just_finished() {
+            return;
+        }
+        journal.probing = true;
+        let probe = backend.get_player_sword_counts();
+        chain.spawn(&mut commands, async move {
+            let answered = probe.await.is_ok();
+            move |world: &mut World| {
+                let mut journal = world.resource_mut::<PickupJournal>();
+                journal.probing = false;
+                journal.reachable = answered;
+            }
+        });
+        return;
+    }
+
+    let journal = &mut *journal;
+    for pickup in &mut journal.pickups {
+        if pickup.state != PickupState::Parked {
+            continue;
+        }
+        if !pickup.hashes.is_empty() {
+            pickup.state = PickupState::Checking;
+            let (id, checking) = (pickup.id, backend.check(pickup.hashes.clone(), false));
+            chain.spawn(&mut commands, async move {
+                let check = checking.await;
+                move |world: &mut World| world.resource_mut::<PickupJournal>().checked(id, check)
+            });
+            continue;
+        }
+        let tx = io.tx_queue.push("pickup claim", ChainCall::IncrementSword { color: pickup.color });
+        io.inventory.record(tx, pickup.color, 1);
+        if !pickup.shown {
+            game_state.swords_collected.push(pickup.color);
+            pickup.shown = true;
+        }
+        pickup.state = PickupState::Queued(tx);
+    }
+}
+
+#[cfg(all(test, not(feature = "chain")))]
+mod tests {
+    use bevy::tasks::block_on;
+
+    use super::*;
+    use crate::backend::ChainBackend;
+    use crate::mock_backend::{MockBackend, MockConfig};
+    use crate::test_app;
+
+    fn journal_path(test: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("pickups-{}-{}.jsonl", test, std::process::id()));
+        let _ = fs::remove_file(&path);
+        path
+    }
+
+    /// Picks up a sword of `color`, the way `collect_swords` does.
+    fn pick_up(world: &mut World, color: u8) {
+        world.resource_mut::<GameState>().swords_collected.push(color);
+        world.resource_mut::<PickupJournal>().record(color);
+    }
+
+    fn synced(world: &World) -> bool {
+        world.resource::<PickupJournal>().pickups.is_empty() && world.resource::<Inventory>().pending() == 0
+    }
+
+    #[test]
+    fn pickups_made_before_connecting_are_sent_once_connected() {
+        let path = journal_path("offline");
+        let backend = MockBackend::new(MockConfig::default());
+        let mut app = test_app::app();
+        pick_up(app.world_mut(), 0);
+        app.world_mut().resource_mut::<PickupJournal>().open_file(path.clone());
+        pick_up(app.world_mut(), 2);
+
+        test_app::run_frames(&mut app, 20);
+        assert_eq!(app.world().resource::<PickupJournal>().unsynced(), 2);
+        assert_eq!(load(&path).unwrap().len(), 2);
+
+        test_app::connect(&mut app, &backend);
+        test_app::run_until(&mut app, synced);
+        assert_eq!(block_on(backend.get_player_sword_counts()), Ok([1, 0, 1, 0]));
+        assert_eq!(app.world().resource::<GameState>().sword_counts(), [1, 0, 1, 0]);
+        assert!(load(&path).unwrap().is_empty());
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn parked_pickups_go_out_once_the_node_answers_again() {
+        let path = journal_path("parked");
+        let backend = MockBackend::new(MockConfig::default());
+        let mut app = test_app::app();
+        app.world_mut().resource_mut::<PickupJournal>().open_file(path.clone());
+        test_app::connect(&mut app, &backend.with_config(MockConfig { failure_rate: 1.0, ..default() }));
+        pick_up(app.world_mut(), 1);
+
+        // Sending gives up, which parks the pickup; it stays shown meanwhile.
+        test_app::run_until(&mut app, |world| !world.resource::<PickupJournal>().reachable);
+        test_app::run_frames(&mut app, 20);
+        assert_eq!(app.world().resource::<PickupJournal>().unsynced(), 1);
+        assert_eq!(app.world().resource::<GameState>().sword_counts(), [0, 1, 0, 0]);
+
+        test_app::connect(&mut app, &backend);
+        test_app::run_until(&mut app, synced);
+        assert_eq!(block_on(backend.get_player_sword_counts()), Ok([0, 1, 0, 0]));
+        assert_eq!(app.world().resource::<GameState>().sword_counts(), [0, 1, 0, 0]);
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn journaled_pickups_are_replayed_after_a_restart() {
+        let path = journal_path("restart");
+        let stale = H256::repeat_byte(0xaa);
+        let lines = [
+            JournalLine::Picked { pickup: 0, color: 2 },
+            // Sent in the earlier session but never mined.
+            JournalLine::Sent { pickup: 0, tx: stale },
+            JournalLine::Picked { pickup: 1, color: 0 },
+            JournalLine::Picked { pickup: 2, color: 3 },
+            JournalLine::Settled { pickup: 2 },
+        ];
+        let text: String = lines.iter().map(|line| serde_json::to_string(line).unwrap() + "\n").collect();
+        fs::write(&path, text + "{\"event\":\"pic").unwrap();
+
+        let backend = MockBackend::new(MockConfig::default());
+        let mut app = test_app::app();
+        app.world_mut().resource_mut::<PickupJournal>().open_file(path.clone());
+        assert_eq!(app.world().resource::<PickupJournal>().unsynced(), 2);
+        assert!(app.world().resource::<GameState>().swords_collected.is_empty());
+
+        test_app::connect(&mut app, &backend);
+        test_app::run_until(&mut app, synced);
+        assert_eq!(block_on(backend.get_player_sword_counts()), Ok([1, 0, 1, 0]));
+        assert_eq!(app.world().resource::<GameState>().sword_counts(), [1, 0, 1, 0]);
+        assert!(load(&path).unwrap().is_empty());
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn lines_that_do_not_parse_are_skipped() {
+        let path = journal_path("bad-lines");
+        let mut bytes = serde_json::to_vec(&JournalLine::Picked { pickup: 0, color: 1 }).unwrap();
+        bytes.extend_from_slice(b"\nnot json\n\xff\xfe\n");
+        bytes.extend(serde_json::to_vec(&JournalLine::Picked { pickup: 1, color: 2 }).unwrap());
+        fs::write(&path, bytes).unwrap();
+
+        let colors: Vec<u8> = load(&path).unwrap().iter().map(|pickup| pickup.color).collect();
+        assert_eq!(colors, [1, 2]);
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn an_unreadable_journal_is_set_aside_not_overwritten() {
+        // A directory in place of the file fails to read, like a permission error would.
+        let path = journal_path("unreadable");
+        fs::create_dir(&path).unwrap();
+        fs::write(path.join("kept"), "pickups").unwrap();
+
+        let mut app = test_app::app();
+        pick_up(app.world_mut(), 3);
+        app.world_mut().resource_mut::<PickupJournal>().open_file(path.clone());
+
+        let name = path.file_name().unwrap().to_str().unwrap().to_string() + ".corrupt-";
+        let aside = fs::read_dir(path.parent().unwrap())
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .find(|entry| entry.file_name().unwrap().to_str().unwrap().starts_with(&name))
+            .unwrap();
+        assert_eq!(fs::read_to_string(aside.join("kept")).unwrap(), "pickups");
+        assert_eq!(load(&path).unwrap().len(), 1);
+        fs::remove_dir_all(&aside).unwrap();
+        fs::remove_file(&path).unwrap();
+    }
+}
@@ -1,55 +1,232 @@
+use std::sync::Arc;
+
 use bevy::prelude::*;
-//use bevy_stylus_plugin::{StylusPlugin, StylusClient};
+use rand::distributions::{Distribution, WeightedIndex};
 use eyre::Result;
+use backend::{Backend, BackendFuture, ChainBackend, ChainCall};
+#[cfg(feature = "chain")]
+use config::ChainConfig;
+#[cfg(feature = "chain")]
+use ethers::signers::Signer;
+#[cfg(feature = "chain")]
+use ethers_backend::EthersBackend;
+use inventory::{Inventory, InventoryPlugin};
+use journal::{JournalPlugin, PickupJournal};
+#[cfg(not(feature = "chain"))]
+use mock_backend::{MockBackend, MockConfig};
+use runtime::{ChainRuntime, ChainRuntimePlugin};
+use tx_panel::TxPanelPlugin;
+use tx_queue::{TxQueue, TxQueuePlugin};
+#[cfg(feature = "chain")]
+use wallet::{WalletPlugin, WalletUnlocked};
+use world_feed::WorldFeedPlugin;
+use gift::GiftPlugin;
+use compat::ChainStatus;
+use daily::DailyReward;
+use menu::{AppState, MenuPlugin, PlayerProfile};
+use skins::EquippedSkin;
+use tournament::TournamentPlugin;
+
+#[cfg(feature = "chain")]
+mod attest;
+mod backend;
+#[cfg(feature = "chain")]
+mod claims;
+mod compat;
+#[cfg(feature = "chain")]
+mod config;
+mod daily;
+#[cfg(feature = "chain")]
+mod ethers_backend;
+mod gift;
+mod inventory;
+mod journal;
+mod menu;
+#[cfg(not(feature = "chain"))]
+mod mock_backend;
+#[cfg(feature = "chain")]
+mod relay;
+#[cfg(feature = "chain")]
+mod revert;
+mod runtime;
+#[cfg(feature = "chain")]
+mod settlement;
+mod skins;
+#[cfg(all(test, not(feature = "chain")))]
+mod test_app;
+mod tournament;
+mod tx_panel;
+mod tx_queue;
+#[cfg(feature = "chain")]
+mod wallet;
+mod world_feed;
+
+/// How often a failed connection is tried again.
+const RECONNECT_SECS: f32 = 15.0;
 
-pub fn init_game(
-    _commands: Commands,
-    _asset_server: Res<AssetServer>,
-    _sprite_assets: ResMut<SpriteAssets>,
-    //stylus_client: Res<StylusClient>,
-    mut game_state: ResMut<GameState>
+/// Opens a backend for the session's player.
+type Connect = Arc<dyn Fn() -> BackendFuture<Result<Arc<dyn ChainBackend>, String>> + Send + Sync>;
+
+/// Retries the connection while there is no `Backend`, so a node that was
+/// down at startup still gets the pickups journaled in the meantime.
+#[derive(Resource)]
+struct Reconnect {
+    connect: Option<Connect>,
+    connecting: bool,
+    timer: Timer,
+}
+
+/// Connects as the unlocked wallet and starts the session on the node.
+#[cfg(feature = "chain")]
+fn init_game(
+    mut commands: Commands,
+    mut unlocked: EventReader<WalletUnlocked>,
+    mut chain_status: ResMut<ChainStatus>,
+    chain: Res<ChainRuntime>,
+    config: Option<Res<ChainConfig>>,
+    mut journal: ResMut<PickupJournal>,
+    mut reconnect: ResMut<Reconnect>,
 ) {
-    /*
-    if let Some(contract) = &stylus_client.contract {
-        if let Ok((red, green, blue)) = tokio::runtime::Runtime::new()
-            .unwrap()
-            .block_on(contract.get_sword_counts().call()) 
-        {
-            game_state.swords_collected = vec![
-                vec![0; red.as_u64() as usize], // Red swords
-                vec![1; green.as_u64() as usize], // Green swords  
-                vec![2; blue.as_u64() as usize], // Blue swords
-            ].into_iter().flatten().collect();
-        }
+    // Wallets are only offered once the config has loaded.
+    let (Some(WalletUnlocked(wallet)), Some(config)) = (unlocked.read().last(), config) else {
+        return;
+    };
+    chain_status.disabled_reason = None;
+    journal.open(wallet.address());
+    let wallet = wallet.clone();
+    let (address, rpc_url) = (config.address, config.rpc_url.clone());
+    reconnect.connect = Some(Arc::new(move || {
+        let (wallet, rpc_url) = (wallet.clone(), rpc_url.clone());
+        Box::pin(async move {
+            let backend = EthersBackend::connect(address, &rpc_url, wallet).await?;
+            Ok(Arc::new(backend) as Arc<dyn ChainBackend>)
+        })
+    }));
+    connect(&mut commands, &chain, &mut reconnect);
+}
+
+/// Without the `chain` feature the game plays against an in-memory contract,
+/// through the same systems as on-chain play.
+#[cfg(not(feature = "chain"))]
+fn init_game(
+    mut commands: Commands,
+    chain: Res<ChainRuntime>,
+    mut journal: ResMut<PickupJournal>,
+    mut reconnect: ResMut<Reconnect>,
+) {
+    let backend = MockBackend::new(MockConfig::from_env());
+    journal.open(backend.player());
+    reconnect.connect = Some(Arc::new(move || {
+        let backend = backend.clone();
+        Box::pin(async move { Ok(Arc::new(backend) as Arc<dyn ChainBackend>) })
+    }));
+    connect(&mut commands, &chain, &mut reconnect);
+}
+
+fn retry_connection(
+    mut commands: Commands,
+    time: Res<Time>,
+    chain: Res<ChainRuntime>,
+    backend: Option<Res<Backend>>,
+    mut reconnect: ResMut<Reconnect>,
+) {
+    if backend.is_some() || reconnect.connecting || reconnect.connect.is_none() {
+        return;
     }
-    */
+    if reconnect.timer.tick(time.delta()).just_finished() {
+        connect(&mut commands, &chain, &mut reconnect);
+    }
+}
+
+fn connect(commands: &mut Commands, chain: &ChainRuntime, reconnect: &mut Reconnect) {
+    let Some(connect) = reconnect.connect.clone() else {
+        return;
+    };
+    reconnect.connecting = true;
+    reconnect.timer.reset();
+    let done_connecting = |world: &mut World| world.resource_mut::<Reconnect>().connecting = false;
+    chain.spawn_or_else(
+        commands,
+        async move {
+            let apply = match connect().await {
+                Ok(backend) => start_session(backend).await,
+                Err(reason) => chain_disabled(reason),
+            };
+            move |world: &mut World| {
+                done_connecting(world);
+                apply(world);
+            }
+        },
+        // A panic in the backend must not leave the retry timer waiting forever.
+        done_connecting,
+    );
+}
+
+/// Loads the player's state from `backend`. The reads land a few frames
+/// later; the backend is handed to game systems as the `Backend` resource
+/// only once it has passed the compatibility check.
+async fn start_session(backend: Arc<dyn ChainBackend>) -> Box<dyn FnOnce(&mut World) + Send> {
+    if let Err(reason) = backend.check_compatible().await {
+        return chain_disabled(reason);
+    }
+    let snapshot = backend.load_player().await;
+    Box::new(move |world: &mut World| {
+        let mut game_state = world.resource_mut::<GameState>();
+        if let Some(swords) = snapshot.swords {
+            game_state.set_sword_counts(swords);
+        }
+        if let Some(kills) = snapshot.kills {
+            game_state.kills = kills;
+        }
+        if let Some(weights) = snapshot.loot_weights {
+            world.resource_mut::<LootTable>().set_weights(weights);
+        }
+        let mut profile = world.resource_mut::<PlayerProfile>();
+        if snapshot.name.is_some() {
+            profile.name = snapshot.name;
+        }
+        profile.guild = snapshot.guild;
+        if let Some(equipped) = snapshot.equipped_skin {
+            world.resource_mut::<EquippedSkin>().0 = equipped;
+        }
+        if let Some(next_claim) = snapshot.next_daily_claim {
+            world.resource_mut::<DailyReward>().next_claim = next_claim;
+        }
+        if let Some(call) = snapshot.settlement {
+            world.resource_mut::<TxQueue>().push("settlement claim", call);
+        }
+        world.resource_mut::<ChainStatus>().disabled_reason = None;
+        world.insert_resource(Backend(backend));
+    })
+}
+
+fn chain_disabled(reason: String) -> Box<dyn FnOnce(&mut World) + Send> {
+    Box::new(move |world: &mut World| {
+        error!("Chain features disabled: {}", reason);
+        world.resource_mut::<ChainStatus>().disabled_reason = Some(reason);
+    })
 }
 
 fn collect_swords(
     mut commands: Commands,
     mut game_state: ResMut<GameState>,
+    mut profile: ResMut<PlayerProfile>,
     sword_query: Query<(Entity, &Transform, &Sword)>,
-    //stylus_client: Res<StylusClient>,
+    mut journal: ResMut<PickupJournal>,
 ) {
     for (sword_entity, sword_transform, sword) in sword_query.iter() {
         let distance = game_state.player_position.distance(sword_transform.translation);
         if distance < 60.0 {
             game_state.swords_collected.push(sword.color);
-            game_state.swing_color = sword.color;
-            commands.entity(sword_entity).despawn();
-            
-            /*
-            if let Some(contract) = &stylus_client.contract {
-                let contract = contract.clone();
-                let color = sword.color;
-                let stylus_client_ref = stylus_client.clone();
-                std::thread::spawn(move || {
-                    tokio::runtime::Runtime::new().unwrap().block_on(async {
-                        let _ = contract.increment_sword(stylus_client_ref.u8_to_u256(color)).send().await;
-                    });
-                });
+            if let Some(guild) = profile.guild.as_mut() {
+                guild.total_swords += 1;
+            }
+            if (sword.color as usize) < SWING_COLORS {
+                game_state.swing_color = sword.color;
             }
-            */
+            commands.entity(sword_entity).despawn();
+            // Journaled even without a connection; the journal sends it once there is one.
+            journal.record(sword.color);
         }
     }
 }
@@ -68,6 +245,13 @@ struct Sword {
 #[derive(Component)]
 struct ItemDrop;
 
+#[derive(Component)]
+struct HudText;
+
+/// The top-left column the HUD text sits in; other panels add themselves below it.
+#[derive(Component)]
+pub struct HudRoot;
+
 #[derive(Component)]
 struct AnimatedSprite {
     current_frame: u8,
@@ -107,16 +291,12 @@ pub struct GameState {
     swing_frame: u8,
     swing_timer: f32,
     swing_color: u8,
+    kills: u64,
 }
 
-const PLAYER_SPEED: f32 = 400.0;
-const ENEMY_SPAWN_RATE: f32 = 2.0;
-
-fn main() -> Result<()> {
-    App::new()
-        .add_plugins(DefaultPlugins.set(ImagePlugin::default_nearest()))
-        //.add_plugins(StylusPlugin)
-        .insert_resource(GameState {
+impl Default for GameState {
+    fn default() -> Self {
+        GameState {
             swords_collected: Vec::new(),
             player_position: Vec3::ZERO,
             last_direction: Vec3::new(1.0, 0.0, 0.0),
@@ -126,20 +306,116 @@ fn main() -> Result<()> {
             swing_frame: 0,
             swing_timer: 0.0,
             swing_color: 1,
-        })
+            kills: 0,
+        }
+    }
+}
+
+impl GameState {
+    pub fn sword_counts(&self) -> [u64; SWORD_COLORS] {
+        let mut counts = [0; SWORD_COLORS];
+        for &color in &self.swords_collected {
+            counts[color as usize] += 1;
+        }
+        counts
+    }
+
+    pub fn set_sword_counts(&mut self, counts: [u64; SWORD_COLORS]) {
+        self.swords_collected = (0..SWORD_COLORS as u8)
+            .flat_map(|color| std::iter::repeat_n(color, counts[color as usize] as usize))
+            .collect();
+    }
+}
+
+/// Kills not yet sent to the contract. They go out as one `claimKills` per
+/// batch rather than a transaction per enemy.
+#[derive(Resource)]
+pub struct KillBatch {
+    pending: u64,
+    timer: Timer,
+}
+
+/// Drop weights indexed by sword color (red, green, blue, normal). The defaults
+/// mirror the contract's and are replaced by `get_loot_table()` when on-chain.
+#[derive(Resource)]
+pub struct LootTable {
+    distribution: Option<WeightedIndex<u64>>,
+}
+
+impl LootTable {
+    pub fn new(weights: [u64; SWORD_COLORS]) -> Self {
+        // An all-zero table cannot be sampled; `sample` falls back to a uniform roll.
+        LootTable { distribution: WeightedIndex::new(weights).ok() }
+    }
+
+    pub fn set_weights(&mut self, weights: [u64; SWORD_COLORS]) {
+        *self = LootTable::new(weights);
+    }
+
+    pub fn sample(&self) -> u8 {
+        match &self.distribution {
+            Some(distribution) => distribution.sample(&mut rand::thread_rng()) as u8,
+            None => rand::random::<u8>() % SWORD_COLORS as u8,
+        }
+    }
+}
+
+const PLAYER_SPEED: f32 = 400.0;
+const ENEMY_SPAWN_RATE: f32 = 2.0;
+/// Red, green, blue and normal.
+const SWORD_COLORS: usize = 4;
+/// Only red, green and blue have swing animations; normal swords keep the current swing.
+const SWING_COLORS: usize = 3;
+/// Directory under the platform data dir that holds the game's files.
+const DATA_DIR_NAME: &str = "bevy-stylus";
+const DEFAULT_LOOT_WEIGHTS: [u64; SWORD_COLORS] = [3, 3, 3, 1];
+/// A kill batch is sent once it reaches this size or the flush timer fires.
+const KILL_BATCH_SIZE: u64 = 10;
+const KILL_FLUSH_SECS: f32 = 30.0;
+
+/// Per-user directory for the game's own files, falling back to the working
+/// directory on platforms without one.
+pub fn data_dir() -> std::path::PathBuf {
+    dirs::data_dir().map(|dir| dir.join(DATA_DIR_NAME)).unwrap_or_default()
+}
+
+fn main() -> Result<()> {
+    let mut app = App::new();
+    app.add_plugins(DefaultPlugins.set(ImagePlugin::default_nearest()))
+        .add_plugins(MenuPlugin)
+        .add_plugins(GiftPlugin)
+        .add_plugins(TournamentPlugin)
+        .add_plugins((ChainRuntimePlugin, TxQueuePlugin, InventoryPlugin, JournalPlugin, TxPanelPlugin, WorldFeedPlugin))
+        .init_resource::<GameState>()
         .insert_resource(SpriteAssets {
             player_up: Vec::new(),
             player_down: Vec::new(),
             player_left: Vec::new(),
             player_right: Vec::new(),
             enemy: Vec::new(),
-            sword_swings: vec![Vec::new(); 3],
+            sword_swings: vec![Vec::new(); SWING_COLORS],
             item_drops: Vec::new(),
         })
-        .add_systems(Startup, load_assets)        
-        .add_systems(Startup, init_game.after(load_assets))
-        //.add_systems(Startup, init_game.after(bevy_stylus_plugin::init_stylus))
-        .add_systems(Startup, setup.after(init_game))
+        .insert_resource(LootTable::new(DEFAULT_LOOT_WEIGHTS))
+        .init_resource::<ChainStatus>()
+        .insert_resource(KillBatch {
+            pending: 0,
+            timer: Timer::from_seconds(KILL_FLUSH_SECS, TimerMode::Repeating),
+        })
+        .init_resource::<EquippedSkin>()
+        .insert_resource(Reconnect {
+            connect: None,
+            connecting: false,
+            timer: Timer::from_seconds(RECONNECT_SECS, TimerMode::Repeating),
+        })
+        .add_systems(Update, retry_connection)
+        .add_systems(Startup, load_assets)
+        .add_systems(Startup, setup.after(load_assets))
+        // Startup already loaded the first skin; reload only when it changes after that.
+        .add_systems(
+            Update,
+            load_assets.run_if(resource_changed::<EquippedSkin>.and_then(not(resource_added::<EquippedSkin>))),
+        )
         .add_systems(Update, (
             player_movement,
             player_animation,
@@ -149,10 +425,28 @@ fn main() -> Result<()> {
             enemy_animation,
             sword_collision,
             collect_swords,
+            flush_kills,
             update_ui,
-        ))
-        .run();
+        ).run_if(in_state(AppState::InGame)));
+
+    #[cfg(feature = "chain")]
+    {
+        match ChainConfig::load() {
+            Ok(config) => {
+                info!("Playing on {} at {:?}", config.network.as_deref().unwrap_or(&config.rpc_url), config.address);
+                app.insert_resource(config);
+            }
+            Err(err) => {
+                error!("Chain features disabled: {}", err);
+                app.insert_resource(ChainStatus { disabled_reason: Some(format!("Chain config: {}", err)) });
+            }
+        }
+        app.add_plugins(WalletPlugin).add_systems(Update, init_game);
+    }
+    #[cfg(not(feature = "chain"))]
+    app.add_systems(Startup, init_game);
 
+    app.run();
     Ok(())
 }
 
@@ -160,15 +454,23 @@ fn main() -> Result<()> {
 fn load_assets(
     asset_server: Res<AssetServer>,
     mut sprite_assets: ResMut<SpriteAssets>,
+    skin: Res<EquippedSkin>,
 ) {
-    sprite_assets.player_up.push(asset_server.load("sprites/player/up_1.png"));
-    sprite_assets.player_up.push(asset_server.load("sprites/player/up_2.png"));
-    sprite_assets.player_down.push(asset_server.load("sprites/player/down_1.png"));
-    sprite_assets.player_down.push(asset_server.load("sprites/player/down_2.png"));
-    sprite_assets.player_left.push(asset_server.load("sprites/player/left_1.png"));
-    sprite_assets.player_left.push(asset_server.load("sprites/player/left_2.png"));
-    sprite_assets.player_right.push(asset_server.load("sprites/player/right_1.png"));
-    sprite_assets.player_right.push(asset_server.load("sprites/player/right_2.png"));
+    // Also reruns when the equipped skin arrives from the chain, so start clean.
+    sprite_assets.player_up.clear();
+    sprite_assets.player_down.clear();
+    sprite_assets.player_left.clear();
+    sprite_assets.player_right.clear();
+    sprite_assets.enemy.clear();
+    sprite_assets.item_drops.clear();
+    sprite_assets.player_up.push(asset_server.load(skin.sprite_path("player", "up_1.png")));
+    sprite_assets.player_up.push(asset_server.load(skin.sprite_path("player", "up_2.png")));
+    sprite_assets.player_down.push(asset_server.load(skin.sprite_path("player", "down_1.png")));
+    sprite_assets.player_down.push(asset_server.load(skin.sprite_path("player", "down_2.png")));
+    sprite_assets.player_left.push(asset_server.load(skin.sprite_path("player", "left_1.png")));
+    sprite_assets.player_left.push(asset_server.load(skin.sprite_path("player", "left_2.png")));
+    sprite_assets.player_right.push(asset_server.load(skin.sprite_path("player", "right_1.png")));
+    sprite_assets.player_right.push(asset_server.load(skin.sprite_path("player", "right_2.png")));
     
     sprite_assets.enemy.push(asset_server.load("sprites/enemy/enemy_1.png"));
     sprite_assets.enemy.push(asset_server.load("sprites/enemy/enemy_2.png"));
@@ -182,13 +484,13 @@ fn load_assets(
             for frame in 0..4 {
                 let _sprite_idx = dir_idx * 4 + frame;
                 sprite_assets.sword_swings[_color_idx].push(
-                    asset_server.load(&format!("sprites/swords/{}_{}_{}.png", color_name, dir_name, frame + 1))
+                    asset_server.load(skin.sprite_path("swords", &format!("{}_{}_{}.png", color_name, dir_name, frame + 1)))
                 );
             }
         }
     }
     
-    for (_color_idx, color_name) in color_names.iter().enumerate() {
+    for color_name in color_names.iter().chain(["normal"].iter()) {
         sprite_assets.item_drops.push(asset_server.load(&format!("sprites/items/{}.png", color_name)));
     }
 }
@@ -217,24 +519,36 @@ pub fn setup(mut commands: Commands, sprite_assets: Res<SpriteAssets>) {
         },
     ));
 
-    commands.spawn((
-        TextBundle::from_sections([
-            TextSection::new(
-                "Swords: 0 (Start collecting!)",
-                TextStyle {
-                    font_size: 24.0,
-                    color: Color::WHITE,
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    top: Val::Px(10.0),
+                    left: Val::Px(10.0),
+                    flex_direction: FlexDirection::Column,
+                    row_gap: Val::Px(8.0),
                     ..default()
                 },
-            ),
-        ])
-        .with_style(Style {
-            position_type: PositionType::Absolute,
-            top: Val::Px(10.0),
-            left: Val::Px(10.0),
-            ..default()
-        }),
-    ));
+                ..default()
+            },
+            HudRoot,
+        ))
+        .with_children(|hud| {
+            hud.spawn((
+                TextBundle::from_sections([
+                    TextSection::new(
+                        "Swords: 0 (Start collecting!)",
+                        TextStyle {
+                            font_size: 24.0,
+                            color: Color::WHITE,
+                            ..default()
+                        },
+                    ),
+                ]),
+                HudText,
+            ));
+        });
 }
 
 fn player_movement(
@@ -458,9 +772,11 @@ fn enemy_animation(
 
 fn sword_collision(
     mut commands: Commands,
-    game_state: Res<GameState>,
+    mut game_state: ResMut<GameState>,
+    mut kill_batch: ResMut<KillBatch>,
     enemy_query: Query<(Entity, &Transform), With<Enemy>>,
     sprite_assets: Res<SpriteAssets>,
+    loot_table: Res<LootTable>,
 ) {
     if sprite_assets.item_drops.is_empty() {
         return;
@@ -477,8 +793,10 @@ fn sword_collision(
         let distance = sword_position.distance(enemy_transform.translation);
         if distance < 60.0 {
             commands.entity(enemy_entity).despawn();
+            game_state.kills += 1;
+            kill_batch.pending += 1;
             
-            let color = rand::random::<u8>() % 3;
+            let color = loot_table.sample();
             
             if color as usize >= sprite_assets.item_drops.len() {
                 continue;
@@ -501,22 +819,111 @@ fn sword_collision(
     }
 }
 
-fn update_ui(mut text_query: Query<&mut Text>, game_state: Res<GameState>) {
-    if game_state.is_changed() {
-        let mut color_counts = [0u32; 3];
-        for &color in &game_state.swords_collected {
-            color_counts[color as usize] += 1;
-        }
+fn flush_kills(
+    time: Res<Time>,
+    mut kill_batch: ResMut<KillBatch>,
+    backend: Option<Res<Backend>>,
+    mut tx_queue: ResMut<TxQueue>,
+) {
+    let timer_fired = kill_batch.timer.tick(time.delta()).just_finished();
+    if kill_batch.pending == 0 || (kill_batch.pending < KILL_BATCH_SIZE && !timer_fired) {
+        return;
+    }
+    // Kills wait in the batch until there is a connection to send them on.
+    if backend.is_none() {
+        return;
+    }
+    let count = std::mem::take(&mut kill_batch.pending);
+    info!("Recording {} kills", count);
+    tx_queue.push("kills claim", ChainCall::ClaimKills { count });
+}
+
+fn update_ui(
+    mut text_query: Query<&mut Text, With<HudText>>,
+    game_state: Res<GameState>,
+    profile: Res<PlayerProfile>,
+    inventory: Res<Inventory>,
+    journal: Res<PickupJournal>,
+) {
+    let (unconfirmed, unsynced) = (inventory.pending(), journal.unsynced());
+    if game_state.is_changed() || profile.is_changed() || inventory.is_changed() || journal.is_changed() {
+        let color_counts = game_state.sword_counts();
         
-        let color_names = ["Red", "Green", "Blue"];
+        let color_names = ["Red", "Green", "Blue", "Normal"];
         
-        let mut display_text = format!("Total Swords: {}\n", game_state.swords_collected.len());
+        let player_name = profile.name.as_deref().unwrap_or("Anonymous");
+        let mut display_text = match &profile.guild {
+            Some(guild) => format!("Player: [{}] {}\n", guild.tag, player_name),
+            None => format!("Player: {}\n", player_name),
+        };
+        display_text.push_str(&format!("Total Swords: {}  Kills: {}\n", game_state.swords_collected.len(), game_state.kills));
         for (name, count) in color_names.iter().zip(color_counts.iter()) {
             display_text.push_str(&format!("{}: {} ", name, count));
         }
+        if unconfirmed > 0 {
+            display_text.push_str(&format!("({} unconfirmed) ", unconfirmed));
+        }
+        if unsynced > 0 {
+            display_text.push_str(&format!("({} unsynced)", unsynced));
+        }
+        if let Some(guild) = &profile.guild {
+            display_text.push_str(&format!("\nGuild {}: {} swords", guild.tag, guild.total_swords));
+            if let Some(rank) = guild.rank {
+                display_text.push_str(&format!(" (#{})", rank));
+            }
+        }
+        display_text.push_str("\nPress G to gift swords");
         
         for mut text in text_query.iter_mut() {
             text.sections[0].value = display_text.clone();
         }
     }
-}
\ No newline at end of file
+}
+
+#[cfg(all(test, not(feature = "chain")))]
+mod tests {
+    use super::*;
+    use crate::mock_backend::{MockBackend, MockConfig};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    fn kills_app(pending: u64) -> App {
+        let mut app = test_app::app();
+        app.insert_resource(KillBatch { pending, timer: Timer::from_seconds(KILL_FLUSH_SECS, TimerMode::Repeating) })
+            .add_systems(Update, flush_kills);
+        app
+    }
+
+    #[test]
+    fn kills_are_kept_until_there_is_a_backend() {
+        let mut app = kills_app(3);
+        test_app::run_frames(&mut app, KILL_FLUSH_SECS as usize * 2);
+        assert_eq!(app.world().resource::<KillBatch>().pending, 3);
+        assert_eq!(app.world().resource::<TxQueue>().entries().count(), 0);
+
+        test_app::connect(&mut app, &MockBackend::new(MockConfig::default()));
+        test_app::run_until(&mut app, |world| world.resource::<KillBatch>().pending == 0);
+        let queue = app.world().resource::<TxQueue>();
+        assert_eq!(queue.entries().map(|entry| entry.label).collect::<Vec<_>>(), ["kills claim"]);
+    }
+
+    #[test]
+    fn a_panicking_connect_is_retried() {
+        let attempts = Arc::new(AtomicUsize::new(0));
+        let counted = attempts.clone();
+        let mut app = test_app::app();
+        app.insert_resource(Reconnect {
+            connect: Some(Arc::new(move || {
+                counted.fetch_add(1, Ordering::SeqCst);
+                Box::pin(async { panic!("backend blew up") })
+            })),
+            connecting: false,
+            timer: Timer::from_seconds(RECONNECT_SECS, TimerMode::Repeating),
+        })
+        .add_systems(Update, retry_connection);
+
+        // Each attempt panics; the next one only starts if the last one let go of `connecting`.
+        test_app::run_until(&mut app, |world| {
+            attempts.load(Ordering::SeqCst) == 2 && !world.resource::<Reconnect>().connecting
+        });
+    }
+}
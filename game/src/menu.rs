@@ -0,0 +1,334 @@
+use bevy::input::keyboard::{Key, KeyboardInput};
+use bevy::input::ButtonState;
+use bevy::prelude::*;
+#[cfg(feature = "chain")]
+use crate::wallet::{WalletDialog, WalletMode};
+
+use crate::backend::ChainCall;
+use crate::compat::ChainStatus;
+use crate::daily::DailyReward;
+use crate::tx_queue::{ChainIo, TxId, TxQueue, TxStatus};
+
+/// Mirrors the contract's name rules so obviously bad names never cost gas.
+const NAME_MIN_LENGTH: usize = 3;
+const NAME_MAX_LENGTH: usize = 16;
+
+const BUTTON_COLOR: Color = Color::srgb(0.2, 0.2, 0.3);
+const BUTTON_HOVER_COLOR: Color = Color::srgb(0.3, 0.3, 0.45);
+
+#[derive(States, Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum AppState {
+    #[default]
+    MainMenu,
+    InGame,
+    Gifting,
+    TournamentLobby,
+    #[cfg(feature = "chain")]
+    Wallet,
+}
+
+#[derive(Resource, Default)]
+pub struct PlayerProfile {
+    pub name: Option<String>,
+    pub guild: Option<GuildStatus>,
+}
+
+/// The player's guild as read from the contract at startup. The total is
+/// bumped locally on each pickup so the HUD keeps up between reads.
+pub struct GuildStatus {
+    pub tag: String,
+    pub total_swords: u64,
+    /// Position on `getGuildLeaderboard`, counting from 1.
+    pub rank: Option<usize>,
+}
+
+#[derive(Resource, Default)]
+struct NameInput {
+    value: String,
+    status: String,
+    /// A queued `setName` and the name it registers.
+    registering: Option<(TxId, String)>,
+}
+
+#[derive(Component)]
+struct MainMenu;
+
+#[derive(Component)]
+struct NameInputText;
+
+#[derive(Component)]
+struct MenuStatusText;
+
+#[derive(Component)]
+struct DailyRewardText;
+
+#[derive(Component, Clone, Copy)]
+enum MenuButton {
+    RegisterName,
+    Play,
+    Tournaments,
+    ClaimDaily,
+    #[cfg(feature = "chain")]
+    UnlockWallet,
+    #[cfg(feature = "chain")]
+    CreateWallet,
+}
+
+pub struct MenuPlugin;
+
+impl Plugin for MenuPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_state::<AppState>()
+            .init_resource::<PlayerProfile>()
+            .init_resource::<NameInput>()
+            .init_resource::<DailyReward>()
+            .add_systems(OnEnter(AppState::MainMenu), spawn_menu)
+            .add_systems(OnExit(AppState::MainMenu), despawn_menu)
+            .add_systems(Update, (
+                name_input,
+                menu_buttons,
+                update_menu_text,
+                update_daily_text,
+            ).chain().run_if(in_state(AppState::MainMenu)))
+            .add_systems(Update, watch_registration);
+    }
+}
+
+pub fn validate_name(name: &str) -> Result<(), String> {
+    if name.len() < NAME_MIN_LENGTH || name.len() > NAME_MAX_LENGTH {
+        return Err(format!("Names must be {} to {} characters", NAME_MIN_LENGTH, NAME_MAX_LENGTH));
+    }
+    if !name.bytes().all(|byte| byte.is_ascii_lowercase() || byte.is_ascii_digit() || byte == b'_') {
+        return Err("Names may only use a-z, 0-9 and _".to_string());
+    }
+    Ok(())
+}
+
+fn spawn_menu(mut commands: Commands, profile: Res<PlayerProfile>, mut name_input: ResMut<NameInput>) {
+    name_input.value = profile.name.clone().unwrap_or_default();
+    name_input.status = match &name_input.registering {
+        Some((_, name)) => format!("Registering {}...", name),
+        None => String::new(),
+    };
+
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    flex_direction: FlexDirection::Column,
+                    align_items: AlignItems::Center,
+                    justify_content: JustifyContent::Center,
+                    row_gap: Val::Px(16.0),
+                    ..default()
+                },
+                background_color: Color::srgb(0.05, 0.05, 0.1).into(),
+                z_index: ZIndex::Global(10),
+                ..default()
+            },
+            MainMenu,
+        ))
+        .with_children(|parent| {
+            parent.spawn(TextBundle::from_section(
+                "Bevy Stylus",
+                TextStyle { font_size: 48.0, color: Color::WHITE, ..default() },
+            ));
+            parent.spawn((
+                TextBundle::from_section(
+                    "",
+                    TextStyle { font_size: 24.0, color: Color::WHITE, ..default() },
+                ),
+                NameInputText,
+            ));
+            parent.spawn((
+                TextBundle::from_section(
+                    "",
+                    TextStyle { font_size: 18.0, color: Color::srgb(0.8, 0.8, 0.5), ..default() },
+                ),
+                MenuStatusText,
+            ));
+            spawn_button(parent, "Register Name", MenuButton::RegisterName);
+            spawn_button(parent, "Play", MenuButton::Play);
+            spawn_button(parent, "Tournaments", MenuButton::Tournaments);
+            #[cfg(feature = "chain")]
+            spawn_button(parent, "Unlock Wallet", MenuButton::UnlockWallet);
+            #[cfg(feature = "chain")]
+            spawn_button(parent, "Create Wallet", MenuButton::CreateWallet);
+            parent.spawn((
+                TextBundle::from_section(
+                    "",
+                    TextStyle { font_size: 18.0, color: Color::srgb(0.6, 0.9, 0.6), ..default() },
+                ),
+                DailyRewardText,
+            ));
+            spawn_button(parent, "Claim Daily Reward", MenuButton::ClaimDaily);
+        });
+}
+
+fn spawn_button(parent: &mut ChildBuilder, label: &str, button: MenuButton) {
+    parent
+        .spawn((
+            ButtonBundle {
+                style: Style {
+                    width: Val::Px(240.0),
+                    height: Val::Px(48.0),
+                    align_items: AlignItems::Center,
+                    justify_content: JustifyContent::Center,
+                    ..default()
+                },
+                background_color: BUTTON_COLOR.into(),
+                ..default()
+            },
+            button,
+        ))
+        .with_children(|parent| {
+            parent.spawn(TextBundle::from_section(
+                label,
+                TextStyle { font_size: 22.0, color: Color::WHITE, ..default() },
+            ));
+        });
+}
+
+fn despawn_menu(mut commands: Commands, menu_query: Query<Entity, With<MainMenu>>) {
+    for entity in menu_query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+fn name_input(mut keyboard_events: EventReader<KeyboardInput>, mut name_input: ResMut<NameInput>) {
+    for event in keyboard_events.read() {
+        if event.state != ButtonState::Pressed {
+            continue;
+        }
+        match &event.logical_key {
+            Key::Backspace => {
+                name_input.value.pop();
+            }
+            Key::Character(characters) => {
+                for character in characters.chars() {
+                    if name_input.value.len() < NAME_MAX_LENGTH {
+                        name_input.value.push(character.to_ascii_lowercase());
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+fn menu_buttons(
+    mut interaction_query: Query<(&Interaction, &MenuButton, &mut BackgroundColor), Changed<Interaction>>,
+    mut name_input: ResMut<NameInput>,
+    mut profile: ResMut<PlayerProfile>,
+    mut daily_reward: ResMut<DailyReward>,
+    mut next_state: ResMut<NextState<AppState>>,
+    mut io: ChainIo,
+    #[cfg(feature = "chain")]
+    mut wallet_dialog: ResMut<WalletDialog>,
+) {
+    for (interaction, button, mut background) in interaction_query.iter_mut() {
+        match interaction {
+            Interaction::Hovered => *background = BUTTON_HOVER_COLOR.into(),
+            Interaction::None => *background = BUTTON_COLOR.into(),
+            Interaction::Pressed => match button {
+                MenuButton::RegisterName => {
+                    let name = name_input.value.clone();
+                    if let Err(message) = validate_name(&name) {
+                        name_input.status = message;
+                        continue;
+                    }
+                    if let Some((_, pending)) = &name_input.registering {
+                        name_input.status = format!("Still registering {}...", pending);
+                        continue;
+                    }
+                    if io.backend.is_none() {
+                        name_input.status = format!("Playing as {} (not registered)", name);
+                        profile.name = Some(name);
+                        continue;
+                    }
+                    // The name is only taken on once the contract has accepted it.
+                    name_input.status = format!("Registering {}...", name);
+                    let tx = io.tx_queue.push("name registration", ChainCall::SetName(name.clone()));
+                    name_input.registering = Some((tx, name));
+                }
+                MenuButton::Play => next_state.set(AppState::InGame),
+                MenuButton::Tournaments => next_state.set(AppState::TournamentLobby),
+                MenuButton::ClaimDaily => {
+                    // The contract would revert with `DailyCooldown`; skip the wasted transaction.
+                    if daily_reward.remaining().is_some() {
+                        name_input.status = daily_reward.describe();
+                        continue;
+                    }
+                    daily_reward.mark_claimed();
+                    name_input.status = "Daily reward claimed".to_string();
+
+                    if io.backend.is_some() {
+                        io.tx_queue.push("daily claim", ChainCall::ClaimDaily);
+                    }
+                }
+                #[cfg(feature = "chain")]
+                MenuButton::UnlockWallet | MenuButton::CreateWallet => {
+                    if io.backend.is_some() {
+                        name_input.status = "Wallet already unlocked".to_string();
+                        continue;
+                    }
+                    let mode = match button {
+                        MenuButton::CreateWallet => WalletMode::Create,
+                        _ => WalletMode::Unlock,
+                    };
+                    wallet_dialog.open(mode);
+                    next_state.set(AppState::Wallet);
+                }
+            },
+        }
+    }
+}
+
+fn watch_registration(mut name_input: ResMut<NameInput>, mut profile: ResMut<PlayerProfile>, tx_queue: Res<TxQueue>) {
+    let Some((id, name)) = &name_input.registering else {
+        return;
+    };
+    let status = match tx_queue.get(*id).map(|entry| &entry.status) {
+        Some(TxStatus::Confirmed(_)) => {
+            profile.name = Some(name.clone());
+            format!("Registered as {}", name)
+        }
+        Some(TxStatus::Failed(reason)) if reason.starts_with("NameTaken") => format!("{} is already taken", name),
+        Some(TxStatus::Failed(reason) | TxStatus::Unsent(reason)) => format!("Could not register {}: {}", name, reason),
+        Some(_) => return,
+        None => "Lost track of the name registration".to_string(),
+    };
+    name_input.status = status;
+    name_input.registering = None;
+}
+
+fn update_menu_text(
+    name_input: Res<NameInput>,
+    chain_status: Res<ChainStatus>,
+    mut input_query: Query<&mut Text, (With<NameInputText>, Without<MenuStatusText>)>,
+    mut status_query: Query<&mut Text, (With<MenuStatusText>, Without<NameInputText>)>,
+) {
+    if !name_input.is_changed() && !chain_status.is_changed() {
+        return;
+    }
+    for mut text in input_query.iter_mut() {
+        text.sections[0].value = format!("Name: {}_", name_input.value);
+    }
+    let status = match &chain_status.disabled_reason {
+        Some(reason) => format!("Playing offline: {}\n{}", reason, name_input.status),
+        None => name_input.status.clone(),
+    };
+    for mut text in status_query.iter_mut() {
+        text.sections[0].value = status.clone();
+    }
+}
+
+fn update_daily_text(daily_reward: Res<DailyReward>, mut text_query: Query<&mut Text, With<DailyRewardText>>) {
+    let description = daily_reward.describe();
+    for mut text in text_query.iter_mut() {
+        if text.sections[0].value != description {
+            text.sections[0].value = description.clone();
+        }
+    }
+}
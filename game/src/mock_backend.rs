@@ -0,0 +1,289 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::mpsc::Sender;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use ethers::types::{Address, H256};
+
+use crate::backend::{BackendFuture, ChainBackend, ChainCall, PlayerSnapshot, SendError, SwordFound, TxCheck};
+use crate::tournament::TournamentInfo;
+use crate::SWORD_COLORS;
+
+/// Delay added to every call, in milliseconds.
+const MOCK_LATENCY_MS: &str = "MOCK_LATENCY_MS";
+/// Chance from 0 to 1 that a call fails as if the node were unreachable.
+const MOCK_FAILURE_RATE: &str = "MOCK_FAILURE_RATE";
+/// Chance from 0 to 1 that a transaction waits for a fee bump to be mined.
+const MOCK_STUCK_RATE: &str = "MOCK_STUCK_RATE";
+
+/// Mirrors the contract's `DAILY_COOLDOWN` and default daily bundle.
+const DAILY_COOLDOWN_SECS: u64 = 24 * 60 * 60;
+const DAILY_REWARD: [u64; SWORD_COLORS] = [1, 1, 1, 0];
+/// Mirrors the contract's default loot table.
+const LOOT_WEIGHTS: [u64; SWORD_COLORS] = [3, 3, 3, 1];
+
+#[derive(Clone, Debug, Default)]
+pub struct MockConfig {
+    pub latency: Duration,
+    /// Chance from 0 to 1 that any call fails with a transport error.
+    pub failure_rate: f64,
+    /// Chance from 0 to 1 that a sent transaction stays unmined until it is
+    /// re-sent with higher fees.
+    pub stuck_rate: f64,
+}
+
+impl MockConfig {
+    /// Reads `MOCK_LATENCY_MS`, `MOCK_FAILURE_RATE` and `MOCK_STUCK_RATE`, so
+    /// retries, fee bumps and rollbacks can be tried out without a node.
+    pub fn from_env() -> Self {
+        let var = |name| std::env::var(name).ok().and_then(|value| value.trim().parse::<f64>().ok());
+        MockConfig {
+            latency: Duration::from_millis(var(MOCK_LATENCY_MS).unwrap_or_default().max(0.0) as u64),
+            failure_rate: var(MOCK_FAILURE_RATE).unwrap_or_default().clamp(0.0, 1.0),
+            stuck_rate: var(MOCK_STUCK_RATE).unwrap_or_default().clamp(0.0, 1.0),
+        }
+    }
+}
+
+#[derive(Default)]
+struct MockState {
+    swords: HashMap<Address, [u64; SWORD_COLORS]>,
+    names: HashMap<Address, String>,
+    kills: u64,
+    next_daily_claim: u64,
+    tournaments: Vec<TournamentInfo>,
+    current_tournament: u64,
+    mined: HashSet<H256>,
+    /// Sent but waiting for a fee bump.
+    stuck: HashSet<H256>,
+    subscribers: Vec<Sender<SwordFound>>,
+}
+
+/// The sword contract in memory, for offline play and tests. Calls succeed
+/// immediately unless `MockConfig` adds latency or failures, and every
+/// transaction is mined by the next receipt check unless it got stuck.
+#[derive(Clone)]
+pub struct MockBackend {
+    config: MockConfig,
+    player: Address,
+    state: Arc<Mutex<MockState>>,
+}
+
+impl MockBackend {
+    pub fn new(config: MockConfig) -> Self {
+        MockBackend { config, player: Address::repeat_byte(0x01), state: Arc::default() }
+    }
+
+    /// Another connection to the same contract, e.g. a healthy one after a
+    /// flaky one.
+    #[cfg(test)]
+    pub fn with_config(&self, config: MockConfig) -> Self {
+        MockBackend { config, ..self.clone() }
+    }
+
+    /// Waits out the configured latency, then fails if the dice say so.
+    async fn round_trip(&self) -> Result<(), String> {
+        if !self.config.latency.is_zero() {
+            tokio::time::sleep(self.config.latency).await;
+        }
+        if rand::random::<f64>() < self.config.failure_rate {
+            return Err("injected failure".to_string());
+        }
+        Ok(())
+    }
+
+    /// Applies `call` the way the contract would, or returns its revert reason.
+    fn apply(&self, state: &mut MockState, call: ChainCall) -> Result<(), String> {
+        let player = self.player;
+        match call {
+            ChainCall::IncrementSword { color } => {
+                let color = usize::from(color);
+                if color >= SWORD_COLORS {
+                    return Err(format!("InvalidColor(color: {})", color));
+                }
+                state.swords.entry(player).or_default()[color] += 1;
+                let name = state.names.get(&player).cloned();
+                let find = SwordFound { player: Some(player), name, color: color as u8, amount: 1 };
+                state.subscribers.retain(|subscriber| subscriber.send(find.clone()).is_ok());
+            }
+            ChainCall::ClaimKills { count } => state.kills += count,
+            ChainCall::TransferSwords { to, color, amount } => {
+                let color = usize::from(color);
+                if color >= SWORD_COLORS {
+                    return Err(format!("InvalidColor(color: {})", color));
+                }
+                let owned = state.swords.get(&player).map_or(0, |counts| counts[color]);
+                if owned < amount {
+                    return Err(format!("InsufficientSwords(color: {}, balance: {}, requested: {})", color, owned, amount));
+                }
+                state.swords.entry(player).or_default()[color] -= amount;
+                state.swords.entry(to).or_default()[color] += amount;
+            }
+            ChainCall::SetName(name) => {
+                if let Some((owner, _)) = state.names.iter().find(|(&owner, taken)| **taken == name && owner != player) {
+                    return Err(format!("NameTaken(name: \"{}\", owner: {:?})", name, owner));
+                }
+                state.names.insert(player, name);
+            }
+            ChainCall::ClaimDaily => {
+                let now = now_secs();
+                if now < state.next_daily_claim {
+                    return Err(format!(
+                        "DailyCooldown(next_claim_time: {}, remaining: {})",
+                        state.next_daily_claim,
+                        state.next_daily_claim - now
+                    ));
+                }
+                state.next_daily_claim = now + DAILY_COOLDOWN_SECS;
+                let counts = state.swords.entry(player).or_default();
+                for (count, reward) in counts.iter_mut().zip(DAILY_REWARD) {
+                    *count += reward;
+                }
+            }
+            ChainCall::JoinTournament { id, entry_fee } => {
+                let Some(info) = state.tournaments.iter_mut().find(|info| info.id == id) else {
+                    return Err(format!("UnknownTournament(id: {})", id));
+                };
+                info.prize_pool += entry_fee;
+                info.entrants += 1;
+                state.current_tournament = id;
+            }
+        }
+        Ok(())
+    }
+
+    /// A hash for a transaction that just went out, mined unless the dice
+    /// say it is stuck.
+    fn send(&self, state: &mut MockState) -> H256 {
+        // Random, so hashes journaled in an earlier session never match.
+        let tx_hash = H256::random();
+        if rand::random::<f64>() < self.config.stuck_rate {
+            state.stuck.insert(tx_hash);
+        } else {
+            state.mined.insert(tx_hash);
+        }
+        tx_hash
+    }
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|now| now.as_secs()).unwrap_or_default()
+}
+
+impl ChainBackend for MockBackend {
+    fn player(&self) -> Address {
+        self.player
+    }
+
+    fn is_local(&self) -> bool {
+        true
+    }
+
+    fn check_compatible(&self) -> BackendFuture<Result<(), String>> {
+        let this = self.clone();
+        Box::pin(async move { this.round_trip().await })
+    }
+
+    fn load_player(&self) -> BackendFuture<PlayerSnapshot> {
+        let this = self.clone();
+        Box::pin(async move {
+            // Like a node that answers some reads and not others.
+            let Ok(()) = this.round_trip().await else {
+                return PlayerSnapshot::default();
+            };
+            let state = this.state.lock().unwrap();
+            PlayerSnapshot {
+                swords: Some(state.swords.get(&this.player).copied().unwrap_or_default()),
+                loot_weights: Some(LOOT_WEIGHTS),
+                name: state.names.get(&this.player).cloned(),
+                guild: None,
+                kills: Some(state.kills),
+                equipped_skin: None,
+                next_daily_claim: Some(state.next_daily_claim),
+                settlement: None,
+            }
+        })
+    }
+
+    fn get_sword_counts(&self) -> BackendFuture<Result<[u64; SWORD_COLORS], String>> {
+        let this = self.clone();
+        Box::pin(async move {
+            this.round_trip().await?;
+            let state = this.state.lock().unwrap();
+            let mut totals = [0; SWORD_COLORS];
+            for counts in state.swords.values() {
+                for (total, count) in totals.iter_mut().zip(counts) {
+                    *total += count;
+                }
+            }
+            Ok(totals)
+        })
+    }
+
+    fn get_player_sword_counts(&self) -> BackendFuture<Result<[u64; SWORD_COLORS], String>> {
+        let this = self.clone();
+        Box::pin(async move {
+            this.round_trip().await?;
+            Ok(this.state.lock().unwrap().swords.get(&this.player).copied().unwrap_or_default())
+        })
+    }
+
+    fn tournaments(&self) -> BackendFuture<Result<Vec<TournamentInfo>, String>> {
+        let this = self.clone();
+        Box::pin(async move {
+            this.round_trip().await?;
+            let now = now_secs();
+            Ok(this.state.lock().unwrap().tournaments.iter().filter(|info| info.end_time > now).cloned().collect())
+        })
+    }
+
+    fn current_tournament(&self) -> BackendFuture<Result<u64, String>> {
+        let this = self.clone();
+        Box::pin(async move {
+            this.round_trip().await?;
+            Ok(this.state.lock().unwrap().current_tournament)
+        })
+    }
+
+    fn submit(&self, call: ChainCall) -> BackendFuture<Result<H256, SendError>> {
+        let this = self.clone();
+        Box::pin(async move {
+            this.round_trip().await.map_err(SendError::Failed)?;
+            let mut state = this.state.lock().unwrap();
+            this.apply(&mut state, call).map_err(SendError::Reverted)?;
+            Ok(this.send(&mut state))
+        })
+    }
+
+    fn check(&self, hashes: Vec<H256>, stuck: bool) -> BackendFuture<Result<TxCheck, String>> {
+        let this = self.clone();
+        Box::pin(async move {
+            this.round_trip().await?;
+            {
+                // Only hashes this backend handed out can be mined.
+                let state = this.state.lock().unwrap();
+                if let Some(&tx_hash) = hashes.iter().rev().find(|tx_hash| state.mined.contains(tx_hash)) {
+                    return Ok(TxCheck::Mined(tx_hash, Ok(())));
+                }
+                if !stuck || !hashes.iter().any(|tx_hash| state.stuck.contains(tx_hash)) {
+                    return Ok(TxCheck::Pending);
+                }
+            }
+            // The call was applied when first sent, so the replacement only
+            // needs a hash of its own.
+            if let Err(reason) = this.round_trip().await {
+                return Ok(TxCheck::ResendFailed(reason));
+            }
+            let mut state = this.state.lock().unwrap();
+            for tx_hash in &hashes {
+                state.stuck.remove(tx_hash);
+            }
+            Ok(TxCheck::Resent(this.send(&mut state)))
+        })
+    }
+
+    fn subscribe(&self, found: Sender<SwordFound>) -> BackendFuture<()> {
+        self.state.lock().unwrap().subscribers.push(found);
+        Box::pin(async {})
+    }
+}
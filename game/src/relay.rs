@@ -0,0 +1,124 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use ethers::abi::{encode, Token};
+use ethers::signers::{LocalWallet, Signer};
+use ethers::types::transaction::eip712::{EIP712Domain, Eip712};
+use ethers::types::{Address, Bytes, H256, U256};
+use ethers::utils::keccak256;
+use serde::{Deserialize, Serialize};
+
+/// Must match the relayer's EIP-712 domain.
+const DOMAIN_NAME: &str = "BevyStylusRelayer";
+const DOMAIN_VERSION: &str = "1";
+const FORWARD_REQUEST_TYPE: &str =
+    "ForwardRequest(address from,address to,bytes data,uint256 nonce,uint256 deadline)";
+
+/// Well under the relayer's one hour cap.
+const REQUEST_TTL_SECS: u64 = 5 * 60;
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct ForwardRequest {
+    from: Address,
+    to: Address,
+    data: Bytes,
+    nonce: U256,
+    deadline: U256,
+    chain_id: u64,
+}
+
+impl Eip712 for ForwardRequest {
+    type Error = std::convert::Infallible;
+
+    fn domain(&self) -> Result<EIP712Domain, Self::Error> {
+        Ok(EIP712Domain {
+            name: Some(DOMAIN_NAME.to_string()),
+            version: Some(DOMAIN_VERSION.to_string()),
+            chain_id: Some(self.chain_id.into()),
+            verifying_contract: Some(self.to),
+            salt: None,
+        })
+    }
+
+    fn type_hash() -> Result<[u8; 32], Self::Error> {
+        Ok(keccak256(FORWARD_REQUEST_TYPE))
+    }
+
+    fn struct_hash(&self) -> Result<[u8; 32], Self::Error> {
+        Ok(keccak256(encode(&[
+            Token::FixedBytes(Self::type_hash()?.to_vec()),
+            Token::Address(self.from),
+            Token::Address(self.to),
+            Token::FixedBytes(keccak256(&self.data).to_vec()),
+            Token::Uint(self.nonce),
+            Token::Uint(self.deadline),
+        ])))
+    }
+}
+
+#[derive(Serialize)]
+struct SignedRequest {
+    request: ForwardRequest,
+    signature: Bytes,
+}
+
+#[derive(Deserialize)]
+struct NonceResponse {
+    nonce: U256,
+    chain_id: u64,
+    contract: Address,
+}
+
+#[derive(Deserialize)]
+struct RelayResponse {
+    tx_hash: H256,
+}
+
+/// Submits the player's transactions through the local relayer, which pays the
+/// gas. Present only when `RELAYER_URL` is set.
+#[derive(Clone)]
+pub struct RelayClient {
+    url: String,
+    wallet: LocalWallet,
+    http: reqwest::Client,
+}
+
+impl RelayClient {
+    pub fn from_env(wallet: LocalWallet) -> Option<Self> {
+        let url = std::env::var("RELAYER_URL").ok()?;
+        Some(RelayClient { url: url.trim_end_matches('/').to_string(), wallet, http: reqwest::Client::new() })
+    }
+
+    pub async fn send(&self, data: Bytes) -> eyre::Result<H256> {
+        let player = self.wallet.address();
+        let target: NonceResponse = self
+            .http
+            .get(format!("{}/nonce/{:?}", self.url, player))
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+
+        let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+        let request = ForwardRequest {
+            from: player,
+            to: target.contract,
+            data,
+            nonce: target.nonce,
+            deadline: U256::from(now + REQUEST_TTL_SECS),
+            chain_id: target.chain_id,
+        };
+        let signature = self.wallet.sign_hash(H256(request.encode_eip712()?))?;
+
+        let response: RelayResponse = self
+            .http
+            .post(format!("{}/relay", self.url))
+            .json(&SignedRequest { request, signature: signature.to_vec().into() })
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        Ok(response.tx_hash)
+    }
+}
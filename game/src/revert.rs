@@ -0,0 +1,57 @@
+use bevy_stylus_plugin::STYLUSCONTRACT_ABI;
+use ethers::abi::{self, ParamType, Token};
+use ethers::providers::MiddlewareError;
+use ethers::utils::hex;
+
+/// Selectors of Solidity's built-in `Error(string)` and `Panic(uint256)`.
+const ERROR_SELECTOR: [u8; 4] = [0x08, 0xc3, 0x79, 0xa0];
+const PANIC_SELECTOR: [u8; 4] = [0x4e, 0x48, 0x7b, 0x71];
+
+/// The decoded revert reason carried by `err`, if the node returned revert data.
+pub fn reason<E: MiddlewareError>(err: &E) -> Option<String> {
+    let data = err.as_error_response()?.as_revert_data()?;
+    Some(decode(&data))
+}
+
+/// Turns revert data into e.g. `DailyCooldown(next_claim_time: 1700000000, remaining: 3600)`.
+pub fn decode(data: &[u8]) -> String {
+    if data.len() < 4 {
+        return "reverted without a reason".to_string();
+    }
+    let (selector, args) = data.split_at(4);
+    if selector == ERROR_SELECTOR {
+        if let Ok(Some(Token::String(message))) = abi::decode(&[ParamType::String], args).map(|tokens| tokens.into_iter().next()) {
+            return message;
+        }
+    }
+    if selector == PANIC_SELECTOR {
+        if let Ok(Some(Token::Uint(code))) = abi::decode(&[ParamType::Uint(256)], args).map(|tokens| tokens.into_iter().next()) {
+            return format!("panic {:#x}", code);
+        }
+    }
+    // Custom errors come from the contract interface in `Stylus.toml`.
+    for error in STYLUSCONTRACT_ABI.errors() {
+        if error.signature()[..4] != *selector {
+            continue;
+        }
+        if let Ok(tokens) = error.decode(args) {
+            let fields: Vec<String> = error
+                .inputs
+                .iter()
+                .zip(tokens)
+                .map(|(input, token)| format!("{}: {}", input.name, format_token(token)))
+                .collect();
+            return format!("{}({})", error.name, fields.join(", "));
+        }
+    }
+    format!("unknown error 0x{}", hex::encode(selector))
+}
+
+fn format_token(token: Token) -> String {
+    match token {
+        Token::Uint(value) => value.to_string(),
+        Token::Address(address) => format!("{:?}", address),
+        Token::String(value) => format!("\"{}\"", value),
+        other => other.to_string(),
+    }
+}
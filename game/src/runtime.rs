@@ -0,0 +1,71 @@
+use std::future::Future;
+use std::sync::Arc;
+
+use bevy::prelude::*;
+use bevy::tasks::{block_on, poll_once, IoTaskPool, Task};
+
+/// Applies a finished task's result on the main thread.
+type Apply = Box<dyn FnOnce(&mut World) + Send>;
+
+/// One tokio runtime shared by all chain I/O. ethers needs a tokio reactor,
+/// so futures run here and Bevy's `IoTaskPool` only waits on their handles.
+#[derive(Resource, Clone)]
+pub struct ChainRuntime {
+    runtime: Arc<tokio::runtime::Runtime>,
+}
+
+#[derive(Component)]
+struct ChainTask(Task<Apply>);
+
+pub struct ChainRuntimePlugin;
+
+impl Plugin for ChainRuntimePlugin {
+    fn build(&self, app: &mut App) {
+        let runtime = tokio::runtime::Runtime::new().expect("failed to start the chain runtime");
+        app.insert_resource(ChainRuntime { runtime: Arc::new(runtime) })
+            .add_systems(PreUpdate, poll_chain_tasks);
+    }
+}
+
+impl ChainRuntime {
+    /// Runs `future` off the main thread. The closure it resolves to is
+    /// applied to the world by `poll_chain_tasks` once it is ready, so no
+    /// frame ever waits on the network.
+    pub fn spawn<F, A>(&self, commands: &mut Commands, future: F)
+    where
+        F: Future<Output = A> + Send + 'static,
+        A: FnOnce(&mut World) + Send + 'static,
+    {
+        self.spawn_or_else(commands, future, |_| {});
+    }
+
+    /// Like `spawn`, but applies `on_abort` instead if `future` panics, so
+    /// state set while the task runs can be put back.
+    pub fn spawn_or_else<F, A, B>(&self, commands: &mut Commands, future: F, on_abort: B)
+    where
+        F: Future<Output = A> + Send + 'static,
+        A: FnOnce(&mut World) + Send + 'static,
+        B: FnOnce(&mut World) + Send + 'static,
+    {
+        let handle = self.runtime.spawn(future);
+        let task = IoTaskPool::get().spawn(async move {
+            match handle.await {
+                Ok(apply) => Box::new(apply) as Apply,
+                Err(err) => Box::new(move |world: &mut World| {
+                    error!("Chain task failed: {}", err);
+                    on_abort(world);
+                }) as Apply,
+            }
+        });
+        commands.spawn(ChainTask(task));
+    }
+}
+
+fn poll_chain_tasks(mut commands: Commands, mut tasks: Query<(Entity, &mut ChainTask)>) {
+    for (entity, mut task) in tasks.iter_mut() {
+        if let Some(apply) = block_on(poll_once(&mut task.0)) {
+            commands.add(apply);
+            commands.entity(entity).despawn();
+        }
+    }
+}
@@ -0,0 +1,26 @@
+use ethers::types::{Address, U256};
+use sword_merkle::{Claim, Settlement, COLOR_COUNT};
+
+/// Points at the settlement JSON the game server publishes alongside each root.
+const SETTLEMENT_FILE: &str = "SETTLEMENT_FILE";
+
+/// This player's claim from the published settlement, if it proves against the
+/// contract's current `root`. Checking locally avoids paying for a failed claim.
+pub fn load_claim(player: Address, root: [u8; 32]) -> eyre::Result<Option<Claim>> {
+    let Ok(path) = std::env::var(SETTLEMENT_FILE) else {
+        return Ok(None);
+    };
+    let settlement: Settlement = serde_json::from_str(&std::fs::read_to_string(path)?)?;
+    if settlement.root != root {
+        return Ok(None);
+    }
+    Ok(settlement
+        .claim_for(&player.0)
+        .filter(|claim| sword_merkle::verify(&claim.proof, &root, claim.result.leaf()))
+        .cloned())
+}
+
+/// Whether `claim` holds swords beyond what the player already settled.
+pub fn has_unclaimed(claim: &Claim, settled: [U256; COLOR_COUNT]) -> bool {
+    claim.result.color_counts.iter().zip(settled).any(|(&count, settled)| U256::from(count) > settled)
+}
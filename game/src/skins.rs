@@ -0,0 +1,27 @@
+use bevy::asset::io::file::FileAssetReader;
+use bevy::prelude::*;
+
+/// Sprite directories indexed by on-chain skin id. Id 0 is the default art
+/// every player owns; unknown ids render with it too.
+const SKIN_DIRS: [&str; 2] = ["default", "original"];
+
+/// The skin the local player has equipped, read from `equippedSkin` on startup.
+#[derive(Resource, Default)]
+pub struct EquippedSkin(pub u64);
+
+impl EquippedSkin {
+    pub fn dir(&self) -> &'static str {
+        SKIN_DIRS.get(self.0 as usize).copied().unwrap_or(SKIN_DIRS[0])
+    }
+
+    /// Path of `file` under `sprites/<kind>/<skin>/`. A skin need not redraw
+    /// everything; missing files come from the default skin.
+    pub fn sprite_path(&self, kind: &str, file: &str) -> String {
+        let path = format!("sprites/{}/{}/{}", kind, self.dir(), file);
+        if FileAssetReader::get_base_path().join("assets").join(&path).exists() {
+            path
+        } else {
+            format!("sprites/{}/{}/{}", kind, SKIN_DIRS[0], file)
+        }
+    }
+}
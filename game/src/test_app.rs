@@ -0,0 +1,55 @@
+//! A headless app with the chain systems, for tests against `MockBackend`.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use bevy::prelude::*;
+use bevy::time::TimeUpdateStrategy;
+
+use crate::backend::Backend;
+use crate::inventory::InventoryPlugin;
+use crate::journal::JournalPlugin;
+use crate::mock_backend::MockBackend;
+use crate::runtime::ChainRuntimePlugin;
+use crate::tx_queue::{TxQueue, TxQueuePlugin};
+use crate::GameState;
+
+/// Game time per frame; long enough that the journal's probe timer fires
+/// within a few frames.
+const FRAME: Duration = Duration::from_secs(1);
+const MAX_FRAMES: usize = 500;
+
+pub fn app() -> App {
+    let mut app = App::new();
+    app.add_plugins((MinimalPlugins, ChainRuntimePlugin, TxQueuePlugin, InventoryPlugin, JournalPlugin))
+        .init_resource::<GameState>()
+        .insert_resource(TimeUpdateStrategy::ManualDuration(FRAME));
+    app.world_mut().resource_mut::<Time<Virtual>>().set_max_delta(FRAME);
+    app
+}
+
+pub fn connect(app: &mut App, backend: &MockBackend) {
+    app.insert_resource(Backend(Arc::new(backend.clone())));
+}
+
+/// Runs frames until `done` holds, skipping the queue's real-time waits and
+/// giving chain tasks a moment to finish in between.
+pub fn run_until(app: &mut App, mut done: impl FnMut(&World) -> bool) {
+    for _ in 0..MAX_FRAMES {
+        app.world_mut().resource_mut::<TxQueue>().fast_forward();
+        app.update();
+        if done(app.world()) {
+            return;
+        }
+        std::thread::sleep(Duration::from_millis(1));
+    }
+    panic!("condition not reached in {} frames", MAX_FRAMES);
+}
+
+/// Runs a handful of frames, for checking that something does not happen.
+pub fn run_frames(app: &mut App, frames: usize) {
+    for _ in 0..frames {
+        app.update();
+        std::thread::sleep(Duration::from_millis(1));
+    }
+}
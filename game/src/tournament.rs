@@ -0,0 +1,277 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use bevy::input::keyboard::{Key, KeyboardInput};
+use bevy::input::ButtonState;
+use bevy::prelude::*;
+use ethers::types::U256;
+use ethers::utils::format_ether;
+
+use crate::backend::{Backend, ChainCall};
+use crate::menu::AppState;
+use crate::runtime::ChainRuntime;
+use crate::tx_queue::{TxId, TxQueue, TxStatus};
+
+#[derive(Clone, Debug)]
+pub struct TournamentInfo {
+    pub id: u64,
+    pub entry_fee: U256,
+    pub start_time: u64,
+    pub end_time: u64,
+    pub prize_pool: U256,
+    pub entrants: u64,
+}
+
+#[derive(Resource, Default)]
+struct TournamentLobby {
+    tournaments: Vec<TournamentInfo>,
+    selected: usize,
+    status: String,
+    /// A queued join and the tournament it is for.
+    joining: Option<(TxId, TournamentInfo)>,
+}
+
+/// The tournament the player has joined, which drives the in-game countdown.
+#[derive(Resource, Default)]
+pub struct ActiveTournament(pub Option<TournamentInfo>);
+
+#[derive(Component)]
+struct LobbyRoot;
+
+#[derive(Component)]
+struct LobbyText;
+
+#[derive(Component)]
+struct CountdownText;
+
+pub struct TournamentPlugin;
+
+impl Plugin for TournamentPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<TournamentLobby>()
+            .init_resource::<ActiveTournament>()
+            .add_systems(Startup, spawn_countdown)
+            .add_systems(OnEnter(AppState::TournamentLobby), (load_tournaments, spawn_lobby).chain())
+            .add_systems(OnExit(AppState::TournamentLobby), despawn_lobby)
+            .add_systems(Update, (
+                lobby_input,
+                update_lobby_text,
+            ).chain().run_if(in_state(AppState::TournamentLobby)))
+            .add_systems(Update, update_countdown.run_if(in_state(AppState::InGame)))
+            .add_systems(Update, watch_join);
+    }
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|now| now.as_secs()).unwrap_or_default()
+}
+
+fn format_countdown(seconds: u64) -> String {
+    let (hours, minutes, seconds) = (seconds / 3600, seconds / 60 % 60, seconds % 60);
+    if hours > 0 {
+        format!("{}:{:02}:{:02}", hours, minutes, seconds)
+    } else {
+        format!("{:02}:{:02}", minutes, seconds)
+    }
+}
+
+fn load_tournaments(
+    mut commands: Commands,
+    mut lobby: ResMut<TournamentLobby>,
+    mut active: ResMut<ActiveTournament>,
+    backend: Option<Res<Backend>>,
+    chain: Res<ChainRuntime>,
+) {
+    lobby.selected = 0;
+    lobby.status = String::new();
+
+    let Some(backend) = backend else {
+        active.0 = active.0.take().filter(|info| info.end_time > now_secs());
+        lobby.status = "Tournaments need a chain connection".to_string();
+        return;
+    };
+    lobby.tournaments.clear();
+    lobby.status = "Loading tournaments...".to_string();
+    let (listing, current) = (backend.tournaments(), backend.current_tournament());
+    chain.spawn(&mut commands, async move {
+        let tournaments = listing.await;
+        let current = current.await.ok();
+        move |world: &mut World| {
+            let tournaments = tournaments.unwrap_or_else(|err| {
+                warn!("Could not load tournaments: {}", err);
+                Vec::new()
+            });
+            if let Some(current) = current {
+                world.resource_mut::<ActiveTournament>().0 = tournaments.iter().find(|info| info.id == current).cloned();
+            }
+            let mut lobby = world.resource_mut::<TournamentLobby>();
+            lobby.tournaments = tournaments;
+            lobby.status = String::new();
+        }
+    });
+}
+
+fn spawn_lobby(mut commands: Commands) {
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    align_items: AlignItems::Center,
+                    justify_content: JustifyContent::Center,
+                    ..default()
+                },
+                background_color: Color::srgb(0.05, 0.05, 0.1).into(),
+                z_index: ZIndex::Global(10),
+                ..default()
+            },
+            LobbyRoot,
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                TextBundle::from_section(
+                    "",
+                    TextStyle { font_size: 22.0, color: Color::WHITE, ..default() },
+                ),
+                LobbyText,
+            ));
+        });
+}
+
+fn despawn_lobby(mut commands: Commands, lobby_query: Query<Entity, With<LobbyRoot>>) {
+    for entity in lobby_query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+fn lobby_input(
+    mut keyboard_events: EventReader<KeyboardInput>,
+    mut lobby: ResMut<TournamentLobby>,
+    // Only a confirmed join on chain changes the active tournament.
+    active: Res<ActiveTournament>,
+    mut next_state: ResMut<NextState<AppState>>,
+    backend: Option<Res<Backend>>,
+    mut tx_queue: ResMut<TxQueue>,
+) {
+    for event in keyboard_events.read() {
+        if event.state != ButtonState::Pressed {
+            continue;
+        }
+        match &event.logical_key {
+            Key::Escape => next_state.set(AppState::MainMenu),
+            Key::ArrowUp => lobby.selected = lobby.selected.saturating_sub(1),
+            Key::ArrowDown => {
+                if lobby.selected + 1 < lobby.tournaments.len() {
+                    lobby.selected += 1;
+                }
+            }
+            Key::Enter => {
+                let Some(info) = lobby.tournaments.get(lobby.selected).cloned() else {
+                    continue;
+                };
+                if active.0.as_ref().is_some_and(|current| current.end_time > now_secs()) {
+                    lobby.status = "You are already in a running tournament".to_string();
+                    continue;
+                }
+                lobby.status = format!("Joining tournament #{}...", info.id);
+
+                if backend.is_some() {
+                    // The entry fee travels as value, so this is sent from the player's account.
+                    let call = ChainCall::JoinTournament { id: info.id, entry_fee: info.entry_fee };
+                    lobby.joining = Some((tx_queue.push("tournament join", call), info));
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+fn watch_join(mut lobby: ResMut<TournamentLobby>, mut active: ResMut<ActiveTournament>, tx_queue: Res<TxQueue>) {
+    let Some((id, info)) = &lobby.joining else {
+        return;
+    };
+    let status = match tx_queue.get(*id).map(|entry| &entry.status) {
+        Some(TxStatus::Confirmed(_)) => {
+            active.0 = Some(info.clone());
+            format!("Joined tournament #{}", info.id)
+        }
+        Some(TxStatus::Failed(reason) | TxStatus::Unsent(reason)) => format!("Could not join: {}", reason),
+        Some(_) => return,
+        None => "Lost track of the join transaction".to_string(),
+    };
+    lobby.status = status;
+    lobby.joining = None;
+}
+
+fn update_lobby_text(
+    lobby: Res<TournamentLobby>,
+    active: Res<ActiveTournament>,
+    mut text_query: Query<&mut Text, With<LobbyText>>,
+) {
+    if !lobby.is_changed() && !active.is_changed() {
+        return;
+    }
+    let now = now_secs();
+    let mut display_text = "Tournaments\n\n".to_string();
+    if lobby.tournaments.is_empty() {
+        display_text.push_str("No open tournaments\n");
+    }
+    for (index, info) in lobby.tournaments.iter().enumerate() {
+        let cursor = if index == lobby.selected { ">" } else { " " };
+        let joined = if active.0.as_ref().is_some_and(|current| current.id == info.id) { " (joined)" } else { "" };
+        let timing = if now < info.start_time {
+            format!("starts in {}", format_countdown(info.start_time - now))
+        } else {
+            format!("ends in {}", format_countdown(info.end_time.saturating_sub(now)))
+        };
+        display_text.push_str(&format!(
+            "{} #{}  fee {} ETH  pool {} ETH  {} players  {}{}\n",
+            cursor,
+            info.id,
+            format_ether(info.entry_fee),
+            format_ether(info.prize_pool),
+            info.entrants,
+            timing,
+            joined,
+        ));
+    }
+    display_text.push_str(&format!("\n{}\n\nUp/Down: select  Enter: join  Esc: back", lobby.status));
+    for mut text in text_query.iter_mut() {
+        text.sections[0].value = display_text.clone();
+    }
+}
+
+fn spawn_countdown(mut commands: Commands) {
+    commands.spawn((
+        TextBundle::from_section(
+            "",
+            TextStyle { font_size: 24.0, color: Color::srgb(1.0, 0.85, 0.3), ..default() },
+        )
+        .with_style(Style {
+            position_type: PositionType::Absolute,
+            top: Val::Px(10.0),
+            right: Val::Px(10.0),
+            ..default()
+        }),
+        CountdownText,
+    ));
+}
+
+fn update_countdown(active: Res<ActiveTournament>, mut text_query: Query<&mut Text, With<CountdownText>>) {
+    let now = now_secs();
+    let display_text = match &active.0 {
+        Some(info) if now < info.start_time => {
+            format!("Tournament #{} starts in {}", info.id, format_countdown(info.start_time - now))
+        }
+        Some(info) if now < info.end_time => {
+            format!("Tournament #{} ends in {}", info.id, format_countdown(info.end_time - now))
+        }
+        Some(info) => format!("Tournament #{} is over", info.id),
+        None => String::new(),
+    };
+    for mut text in text_query.iter_mut() {
+        if text.sections[0].value != display_text {
+            text.sections[0].value = display_text.clone();
+        }
+    }
+}
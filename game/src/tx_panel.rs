@@ -0,0 +1,133 @@
+use std::time::{Duration, Instant};
+
+use bevy::prelude::*;
+use ethers::types::H256;
+
+use crate::backend::Backend;
+use crate::compat::ChainStatus;
+use crate::tx_queue::{TxEntry, TxQueue, TxStatus};
+use crate::HudRoot;
+
+/// Confirmations stay fully visible this long, then fade out over `FADE`.
+const CONFIRMED_VISIBLE: Duration = Duration::from_secs(4);
+/// Failures stay longer so the reason can be read.
+const FAILED_VISIBLE: Duration = Duration::from_secs(20);
+const FADE: Duration = Duration::from_secs(2);
+/// Most entries shown at once, newest first.
+const MAX_LINES: usize = 6;
+const FONT_SIZE: f32 = 18.0;
+
+const PENDING_COLOR: Color = Color::srgb(0.9, 0.9, 0.9);
+const CONFIRMED_COLOR: Color = Color::srgb(0.4, 0.9, 0.4);
+const FAILED_COLOR: Color = Color::srgb(1.0, 0.4, 0.4);
+
+#[derive(Component)]
+struct TxPanelText;
+
+/// Transaction status panel under the HUD text, so players can see whether
+/// their swords were actually saved.
+pub struct TxPanelPlugin;
+
+impl Plugin for TxPanelPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, (spawn_tx_panel, update_tx_panel).chain());
+    }
+}
+
+fn spawn_tx_panel(mut commands: Commands, hud_query: Query<Entity, Added<HudRoot>>) {
+    for hud in hud_query.iter() {
+        let panel = commands
+            .spawn((TextBundle::from_section("", TextStyle { font_size: FONT_SIZE, color: PENDING_COLOR, ..default() }), TxPanelText))
+            .id();
+        commands.entity(hud).add_child(panel);
+    }
+}
+
+fn short_hash(tx_hash: H256) -> String {
+    let hex = format!("{:?}", tx_hash);
+    format!("{}..{}", &hex[..6], &hex[hex.len() - 4..])
+}
+
+/// When a finished entry starts to fade out; `None` while it is unfinished.
+fn fade_start(entry: &TxEntry) -> Option<Instant> {
+    let visible = match entry.status {
+        TxStatus::Failed(_) | TxStatus::Unsent(_) => FAILED_VISIBLE,
+        _ => CONFIRMED_VISIBLE,
+    };
+    entry.finished_at.map(|finished_at| finished_at + visible)
+}
+
+/// Opacity of an entry, or `None` once it has faded out.
+fn opacity(entry: &TxEntry, now: Instant) -> Option<f32> {
+    let Some(fade_start) = fade_start(entry) else {
+        return Some(1.0);
+    };
+    let fading = now.saturating_duration_since(fade_start);
+    (fading < FADE).then(|| 1.0 - fading.as_secs_f32() / FADE.as_secs_f32())
+}
+
+fn describe(entry: &TxEntry) -> (String, Color) {
+    match &entry.status {
+        TxStatus::Queued if entry.attempts > 0 => {
+            (format!("{}: retrying (attempt {})", entry.label, entry.attempts + 1), PENDING_COLOR)
+        }
+        TxStatus::Queued => (format!("{}: queued", entry.label), PENDING_COLOR),
+        TxStatus::Submitting => (format!("{}: sending", entry.label), PENDING_COLOR),
+        TxStatus::Pending(tx_hash) => (format!("{}: pending {}", entry.label, short_hash(*tx_hash)), PENDING_COLOR),
+        TxStatus::Confirmed(tx_hash) => {
+            (format!("{}: confirmed {}", entry.label, short_hash(*tx_hash)), CONFIRMED_COLOR)
+        }
+        TxStatus::Failed(reason) => (format!("{}: failed, {}", entry.label, reason), FAILED_COLOR),
+        TxStatus::Unsent(reason) => (format!("{}: not sent, {}", entry.label, reason), FAILED_COLOR),
+    }
+}
+
+/// Rebuilds the panel when the queue or the connection changes, and while
+/// an entry is fading out.
+fn update_tx_panel(
+    tx_queue: Res<TxQueue>,
+    backend: Option<Res<Backend>>,
+    chain_status: Res<ChainStatus>,
+    mut text_query: Query<&mut Text, With<TxPanelText>>,
+    new_panels: Query<(), Added<TxPanelText>>,
+    mut next_fade: Local<Option<Instant>>,
+) {
+    let now = Instant::now();
+    let changed = tx_queue.is_changed()
+        || chain_status.is_changed()
+        || backend.as_ref().is_some_and(|backend| backend.is_added())
+        || !new_panels.is_empty();
+    if !changed && next_fade.is_none_or(|at| now < at) {
+        return;
+    }
+
+    let connection = match (backend.as_deref(), &chain_status.disabled_reason) {
+        (Some(backend), _) if backend.is_local() => "local",
+        (Some(_), _) => "connected",
+        (None, Some(_)) => "offline",
+        (None, None) => "connecting",
+    };
+    let style = TextStyle { font_size: FONT_SIZE, color: PENDING_COLOR, ..default() };
+    let mut sections = vec![TextSection::new(
+        format!("Chain: {}  Pending: {}\n", connection, tx_queue.unfinished()),
+        style.clone(),
+    )];
+    let lines = tx_queue
+        .entries()
+        .rev()
+        .filter_map(|entry| opacity(entry, now).map(|alpha| (entry, alpha)))
+        .take(MAX_LINES);
+    // Fading entries need a rebuild every frame; otherwise wait for the next fade to start.
+    *next_fade = None;
+    for (entry, alpha) in lines {
+        let (line, color) = describe(entry);
+        sections.push(TextSection::new(format!("{}\n", line), TextStyle { color: color.with_alpha(alpha), ..style.clone() }));
+        if let Some(fade_start) = fade_start(entry) {
+            let at = fade_start.max(now);
+            *next_fade = Some(next_fade.map_or(at, |next| next.min(at)));
+        }
+    }
+    for mut text in text_query.iter_mut() {
+        text.sections = sections.clone();
+    }
+}
@@ -0,0 +1,376 @@
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+use ethers::types::H256;
+
+use crate::backend::{Backend, ChainCall, SendError, TxCheck};
+use crate::inventory::Inventory;
+use crate::runtime::ChainRuntime;
+
+/// Send errors are retried this many times before the entry is marked failed.
+const MAX_ATTEMPTS: u32 = 6;
+const BACKOFF_BASE: Duration = Duration::from_secs(1);
+const BACKOFF_MAX: Duration = Duration::from_secs(60);
+const RECEIPT_POLL: Duration = Duration::from_secs(2);
+/// A direct transaction without a receipt after this long is re-sent with higher fees.
+const STUCK_AFTER: Duration = Duration::from_secs(30);
+const MAX_FEE_BUMPS: u32 = 5;
+/// A transaction still unmined this long after it was first sent was most
+/// likely dropped by the node or the relayer. The entry gives up so it no
+/// longer holds up the queue; the journal replays pickups it gave up on.
+const GIVE_UP_AFTER: Duration = Duration::from_secs(10 * 60);
+/// Confirmed and failed entries kept around for other systems to show.
+const FINISHED_HISTORY: usize = 20;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct TxId(u64);
+
+/// What a system needs to change chain state: the connection, if any, the
+/// queue calls go into, and the inventory their expected effects go into.
+#[derive(SystemParam)]
+pub struct ChainIo<'w> {
+    pub backend: Option<Res<'w, Backend>>,
+    pub tx_queue: ResMut<'w, TxQueue>,
+    pub inventory: ResMut<'w, Inventory>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum TxStatus {
+    Queued,
+    Submitting,
+    Pending(H256),
+    Confirmed(H256),
+    Failed(String),
+    /// Never reached the chain; sending kept failing until the queue gave up.
+    Unsent(String),
+}
+
+impl TxStatus {
+    pub fn is_finished(&self) -> bool {
+        matches!(self, TxStatus::Confirmed(_) | TxStatus::Failed(_) | TxStatus::Unsent(_))
+    }
+}
+
+pub struct TxEntry {
+    pub id: TxId,
+    pub label: &'static str,
+    pub status: TxStatus,
+    pub attempts: u32,
+    /// When the entry was confirmed or gave up.
+    pub finished_at: Option<Instant>,
+    call: ChainCall,
+    /// Every hash sent for this entry; a fee bump replaces the transaction
+    /// but the earlier one may still be the one that gets mined.
+    hashes: Vec<H256>,
+    fee_bumps: u32,
+    /// When the first transaction for this entry went out.
+    first_sent_at: Option<Instant>,
+    submitted_at: Instant,
+    next_attempt: Instant,
+}
+
+/// Outgoing transactions, sent one at a time in the order they were queued.
+/// Claims are signed with sequential contract nonces and direct sends use
+/// sequential account nonces, so nothing is sent until the previous entry has
+/// a receipt or has given up.
+#[derive(Resource, Default)]
+pub struct TxQueue {
+    entries: VecDeque<TxEntry>,
+    next_id: u64,
+    /// A send or receipt check is in flight on the chain runtime.
+    busy: bool,
+}
+
+pub struct TxQueuePlugin;
+
+impl Plugin for TxQueuePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<TxQueue>().add_systems(Update, drive_tx_queue);
+    }
+}
+
+impl TxQueue {
+    pub fn push(&mut self, label: &'static str, call: ChainCall) -> TxId {
+        let id = TxId(self.next_id);
+        self.next_id += 1;
+        let now = Instant::now();
+        self.entries.push_back(TxEntry {
+            id,
+            label,
+            status: TxStatus::Queued,
+            attempts: 0,
+            finished_at: None,
+            call,
+            hashes: Vec::new(),
+            fee_bumps: 0,
+            first_sent_at: None,
+            submitted_at: now,
+            next_attempt: now,
+        });
+        id
+    }
+
+    pub fn get(&self, id: TxId) -> Option<&TxEntry> {
+        self.entries.iter().find(|entry| entry.id == id)
+    }
+
+    pub fn entries(&self) -> impl DoubleEndedIterator<Item = &TxEntry> {
+        self.entries.iter()
+    }
+
+    pub fn unfinished(&self) -> usize {
+        self.entries.iter().filter(|entry| !entry.status.is_finished()).count()
+    }
+
+    fn submitted(&mut self, id: TxId, result: Result<H256, SendError>) {
+        self.busy = false;
+        let Some(entry) = self.entries.iter_mut().find(|entry| entry.id == id) else {
+            return;
+        };
+        let now = Instant::now();
+        match result {
+            Ok(tx_hash) => {
+                info!("Sent {} in {:?}", entry.label, tx_hash);
+                entry.status = TxStatus::Pending(tx_hash);
+                entry.hashes.push(tx_hash);
+                entry.first_sent_at.get_or_insert(now);
+                entry.submitted_at = now;
+                entry.next_attempt = now + RECEIPT_POLL;
+            }
+            // Reverts are deterministic, so retrying them only burns gas.
+            Err(SendError::Reverted(reason)) => {
+                warn!("{} would revert: {}", entry.label, reason);
+                entry.status = TxStatus::Failed(reason);
+                entry.finished_at = Some(now);
+            }
+            Err(SendError::Failed(reason)) if entry.attempts >= MAX_ATTEMPTS => {
+                warn!("Giving up on {}: {}", entry.label, reason);
+                entry.status = TxStatus::Unsent(reason);
+                entry.finished_at = Some(now);
+            }
+            Err(SendError::Failed(reason)) => {
+                let delay = backoff(entry.attempts);
+                warn!("Could not send {}, retrying in {:?}: {}", entry.label, delay, reason);
+                entry.status = TxStatus::Queued;
+                entry.next_attempt = now + delay;
+            }
+        }
+        self.prune();
+    }
+
+    fn checked(&mut self, id: TxId, check: Result<TxCheck, String>) {
+        self.busy = false;
+        let Some(entry) = self.entries.iter_mut().find(|entry| entry.id == id) else {
+            return;
+        };
+        let now = Instant::now();
+        entry.next_attempt = now + RECEIPT_POLL;
+        match check {
+            Ok(TxCheck::Mined(tx_hash, Ok(()))) => {
+                info!("Confirmed {} in {:?}", entry.label, tx_hash);
+                entry.status = TxStatus::Confirmed(tx_hash);
+                entry.finished_at = Some(now);
+            }
+            Ok(TxCheck::Mined(tx_hash, Err(reason))) => {
+                warn!("{} reverted in {:?}: {}", entry.label, tx_hash, reason);
+                entry.status = TxStatus::Failed(reason);
+                entry.finished_at = Some(now);
+            }
+            Ok(TxCheck::Resent(tx_hash)) => {
+                info!("Re-sent {} with higher fees in {:?}", entry.label, tx_hash);
+                entry.status = TxStatus::Pending(tx_hash);
+                entry.hashes.push(tx_hash);
+                entry.fee_bumps += 1;
+                entry.submitted_at = now;
+            }
+            // The replacement is refused once the original is mined; the
+            // next receipt check picks that up.
+            Ok(TxCheck::ResendFailed(reason)) => warn!("Could not bump fees for {}: {}", entry.label, reason),
+            Ok(TxCheck::Pending) => {}
+            Err(reason) => warn!("Could not check {}: {}", entry.label, reason),
+        }
+        self.prune();
+    }
+
+    fn prune(&mut self) {
+        let mut finished = self.entries.iter().filter(|entry| entry.status.is_finished()).count();
+        self.entries.retain(|entry| {
+            if finished > FINISHED_HISTORY && entry.status.is_finished() {
+                finished -= 1;
+                false
+            } else {
+                true
+            }
+        });
+    }
+}
+
+#[cfg(all(test, not(feature = "chain")))]
+impl TxQueue {
+    /// Lets every entry act on the next frame, as if backoffs, receipt polls
+    /// and the stuck timeout had all passed.
+    pub fn fast_forward(&mut self) {
+        let now = Instant::now();
+        for entry in &mut self.entries {
+            entry.next_attempt = now;
+            entry.submitted_at = now.checked_sub(STUCK_AFTER).unwrap_or(entry.submitted_at);
+        }
+    }
+}
+
+fn backoff(attempts: u32) -> Duration {
+    BACKOFF_BASE.saturating_mul(1 << attempts.saturating_sub(1).min(16)).min(BACKOFF_MAX)
+}
+
+fn drive_tx_queue(
+    mut commands: Commands,
+    mut queue: ResMut<TxQueue>,
+    backend: Option<Res<Backend>>,
+    chain: Res<ChainRuntime>,
+) {
+    let Some(backend) = backend else {
+        return;
+    };
+    if queue.busy {
+        return;
+    }
+    let now = Instant::now();
+    let Some(entry) = queue.entries.iter_mut().find(|entry| !entry.status.is_finished()) else {
+        return;
+    };
+    if now < entry.next_attempt {
+        return;
+    }
+    let id = entry.id;
+    match entry.status {
+        TxStatus::Queued => {
+            entry.status = TxStatus::Submitting;
+            entry.attempts += 1;
+            let sending = backend.submit(entry.call.clone());
+            chain.spawn(&mut commands, async move {
+                let sent = sending.await;
+                move |world: &mut World| world.resource_mut::<TxQueue>().submitted(id, sent)
+            });
+        }
+        TxStatus::Pending(_) if entry.first_sent_at.is_some_and(|sent_at| now.duration_since(sent_at) > GIVE_UP_AFTER) => {
+            warn!("Giving up on {}, not mined after {:?}", entry.label, GIVE_UP_AFTER);
+            entry.status = TxStatus::Unsent("not mined".to_string());
+            entry.finished_at = Some(now);
+            backend.abandon(entry.hashes.clone());
+            queue.prune();
+            return;
+        }
+        TxStatus::Pending(_) => {
+            let stuck = entry.fee_bumps < MAX_FEE_BUMPS && now.duration_since(entry.submitted_at) > STUCK_AFTER;
+            let checking = backend.check(entry.hashes.clone(), stuck);
+            chain.spawn(&mut commands, async move {
+                let check = checking.await;
+                move |world: &mut World| world.resource_mut::<TxQueue>().checked(id, check)
+            });
+        }
+        TxStatus::Submitting | TxStatus::Confirmed(_) | TxStatus::Failed(_) | TxStatus::Unsent(_) => return,
+    }
+    queue.busy = true;
+}
+
+#[cfg(all(test, not(feature = "chain")))]
+mod tests {
+    use bevy::tasks::block_on;
+    use ethers::types::Address;
+
+    use super::*;
+    use crate::backend::ChainBackend;
+    use crate::mock_backend::{MockBackend, MockConfig};
+    use crate::test_app;
+
+    fn status(world: &World, id: TxId) -> Option<TxStatus> {
+        world.resource::<TxQueue>().get(id).map(|entry| entry.status.clone())
+    }
+
+    #[test]
+    fn queued_calls_are_confirmed_in_order() {
+        let backend = MockBackend::new(MockConfig::default());
+        let mut app = test_app::app();
+        test_app::connect(&mut app, &backend);
+        let mut queue = app.world_mut().resource_mut::<TxQueue>();
+        let first = queue.push("pickup claim", ChainCall::IncrementSword { color: 0 });
+        let second = queue.push("pickup claim", ChainCall::IncrementSword { color: 2 });
+
+        test_app::run_until(&mut app, |world| matches!(status(world, first), Some(TxStatus::Confirmed(_))));
+        assert!(!matches!(status(app.world(), second), Some(TxStatus::Confirmed(_))));
+        test_app::run_until(&mut app, |world| matches!(status(world, second), Some(TxStatus::Confirmed(_))));
+        assert_eq!(block_on(backend.get_player_sword_counts()), Ok([1, 0, 1, 0]));
+    }
+
+    #[test]
+    fn send_failures_give_up_after_max_attempts() {
+        let backend = MockBackend::new(MockConfig { failure_rate: 1.0, ..default() });
+        let mut app = test_app::app();
+        test_app::connect(&mut app, &backend);
+        let id = app.world_mut().resource_mut::<TxQueue>().push("daily claim", ChainCall::ClaimDaily);
+
+        test_app::run_until(&mut app, |world| status(world, id).is_some_and(|status| status.is_finished()));
+        let queue = app.world().resource::<TxQueue>();
+        let entry = queue.get(id).unwrap();
+        assert_eq!(entry.status, TxStatus::Unsent("injected failure".to_string()));
+        assert_eq!(entry.attempts, MAX_ATTEMPTS);
+    }
+
+    #[test]
+    fn reverts_fail_without_a_retry() {
+        let backend = MockBackend::new(MockConfig::default());
+        let mut app = test_app::app();
+        test_app::connect(&mut app, &backend);
+        let call = ChainCall::TransferSwords { to: Address::repeat_byte(0x02), color: 9, amount: 1 };
+        let id = app.world_mut().resource_mut::<TxQueue>().push("gift", call);
+
+        test_app::run_until(&mut app, |world| status(world, id).is_some_and(|status| status.is_finished()));
+        let entry = app.world().resource::<TxQueue>().get(id).unwrap();
+        assert_eq!(entry.status, TxStatus::Failed("InvalidColor(color: 9)".to_string()));
+        assert_eq!(entry.attempts, 1);
+    }
+
+    fn fee_bumps(world: &World, id: TxId) -> u32 {
+        world.resource::<TxQueue>().get(id).map_or(0, |entry| entry.fee_bumps)
+    }
+
+    #[test]
+    fn stuck_transactions_are_resent_with_higher_fees() {
+        let backend = MockBackend::new(MockConfig::default());
+        let mut app = test_app::app();
+        test_app::connect(&mut app, &backend.with_config(MockConfig { stuck_rate: 1.0, ..default() }));
+        let id = app.world_mut().resource_mut::<TxQueue>().push("pickup claim", ChainCall::IncrementSword { color: 1 });
+
+        test_app::run_until(&mut app, |world| fee_bumps(world, id) == 1);
+        test_app::connect(&mut app, &backend);
+        test_app::run_until(&mut app, |world| status(world, id).is_some_and(|status| status.is_finished()));
+        let entry = app.world().resource::<TxQueue>().get(id).unwrap();
+        assert_eq!(entry.status, TxStatus::Confirmed(entry.hashes[2]));
+        assert_eq!((entry.fee_bumps, entry.hashes.len()), (2, 3));
+    }
+
+    #[test]
+    fn never_mined_transactions_give_up_and_free_the_queue() {
+        let backend = MockBackend::new(MockConfig::default());
+        let mut app = test_app::app();
+        test_app::connect(&mut app, &backend.with_config(MockConfig { stuck_rate: 1.0, ..default() }));
+        let mut queue = app.world_mut().resource_mut::<TxQueue>();
+        let stuck = queue.push("pickup claim", ChainCall::IncrementSword { color: 1 });
+        let behind = queue.push("daily claim", ChainCall::ClaimDaily);
+
+        test_app::run_until(&mut app, |world| fee_bumps(world, stuck) == MAX_FEE_BUMPS);
+        test_app::run_frames(&mut app, 20);
+        assert!(matches!(status(app.world(), stuck), Some(TxStatus::Pending(_))));
+        assert_eq!(fee_bumps(app.world(), stuck), MAX_FEE_BUMPS);
+        assert_eq!(status(app.world(), behind), Some(TxStatus::Queued));
+
+        let mut queue = app.world_mut().resource_mut::<TxQueue>();
+        let entry = queue.entries.iter_mut().find(|entry| entry.id == stuck).unwrap();
+        entry.first_sent_at = Instant::now().checked_sub(GIVE_UP_AFTER);
+        test_app::connect(&mut app, &backend);
+        test_app::run_until(&mut app, |world| matches!(status(world, behind), Some(TxStatus::Confirmed(_))));
+        assert_eq!(status(app.world(), stuck), Some(TxStatus::Unsent("not mined".to_string())));
+    }
+}
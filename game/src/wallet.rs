@@ -0,0 +1,300 @@
+use std::path::PathBuf;
+
+use bevy::input::keyboard::{Key, KeyboardInput};
+use bevy::input::ButtonState;
+use bevy::prelude::*;
+use ethers::signers::{LocalWallet, Signer};
+use zeroize::Zeroizing;
+
+use crate::compat::ChainStatus;
+use crate::config::ChainConfig;
+use crate::menu::AppState;
+use crate::runtime::ChainRuntime;
+
+/// New keystores need at least this long a passphrase.
+const MIN_PASSPHRASE_LENGTH: usize = 8;
+/// Passphrase buffers are allocated once at this size and never grow, so no
+/// copy of a passphrase is left behind in a freed allocation.
+const MAX_PASSPHRASE_BYTES: usize = 128;
+
+/// The player's key is ready; `init_game` connects with it.
+#[derive(Event)]
+pub struct WalletUnlocked(pub LocalWallet);
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum WalletMode {
+    Unlock,
+    Create,
+}
+
+#[derive(Resource)]
+pub struct WalletDialog {
+    mode: WalletMode,
+    passphrase: Zeroizing<String>,
+    /// The first entry while a new passphrase is typed again to confirm it.
+    first_entry: Option<Zeroizing<String>>,
+    status: String,
+    /// Scrypt takes a moment; the dialog stays up until it is done.
+    busy: bool,
+}
+
+impl Default for WalletDialog {
+    fn default() -> Self {
+        WalletDialog {
+            mode: WalletMode::Unlock,
+            passphrase: passphrase_buffer(),
+            first_entry: None,
+            status: String::new(),
+            busy: false,
+        }
+    }
+}
+
+impl WalletDialog {
+    pub fn open(&mut self, mode: WalletMode) {
+        self.mode = mode;
+        self.passphrase = passphrase_buffer();
+        self.first_entry = None;
+        self.status = String::new();
+    }
+}
+
+fn passphrase_buffer() -> Zeroizing<String> {
+    Zeroizing::new(String::with_capacity(MAX_PASSPHRASE_BYTES))
+}
+
+#[derive(Component)]
+struct WalletDialogRoot;
+
+#[derive(Component)]
+struct WalletDialogText;
+
+/// Unlocks the player's encrypted keystore, or creates one, through a
+/// passphrase dialog over the main menu.
+pub struct WalletPlugin;
+
+impl Plugin for WalletPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<WalletUnlocked>()
+            .init_resource::<WalletDialog>()
+            .add_systems(Startup, prompt_for_wallet)
+            .add_systems(OnEnter(AppState::Wallet), spawn_wallet_dialog)
+            .add_systems(OnExit(AppState::Wallet), despawn_wallet_dialog)
+            .add_systems(Update, (
+                wallet_input,
+                update_wallet_text,
+            ).chain().run_if(in_state(AppState::Wallet)));
+    }
+}
+
+fn prompt_for_wallet(
+    #[cfg(feature = "dev-keys")]
+    mut unlocked: EventWriter<WalletUnlocked>,
+    mut config: Option<ResMut<ChainConfig>>,
+    mut chain_status: ResMut<ChainStatus>,
+    mut dialog: ResMut<WalletDialog>,
+    mut next_state: ResMut<NextState<AppState>>,
+) {
+    let Some(config) = config.as_deref_mut() else {
+        return;
+    };
+    #[cfg(feature = "dev-keys")]
+    if let Some(wallet) = config.dev_wallet.take() {
+        warn!("Playing as {:?} with the plaintext PRIVATE_KEY; never ship a dev-keys build", wallet.address());
+        unlocked.send(WalletUnlocked(wallet));
+        return;
+    }
+    if config.keystore.exists() {
+        chain_status.disabled_reason = Some("wallet is locked".to_string());
+        dialog.open(WalletMode::Unlock);
+        next_state.set(AppState::Wallet);
+    } else {
+        chain_status.disabled_reason = Some("no wallet yet, create one below".to_string());
+    }
+}
+
+fn spawn_wallet_dialog(mut commands: Commands) {
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    align_items: AlignItems::Center,
+                    justify_content: JustifyContent::Center,
+                    ..default()
+                },
+                background_color: Color::srgb(0.05, 0.05, 0.1).into(),
+                z_index: ZIndex::Global(10),
+                ..default()
+            },
+            WalletDialogRoot,
+        ))
+        .with_children(|parent| {
+            parent
+                .spawn(NodeBundle {
+                    style: Style {
+                        padding: UiRect::all(Val::Px(20.0)),
+                        ..default()
+                    },
+                    background_color: Color::srgb(0.1, 0.1, 0.2).into(),
+                    ..default()
+                })
+                .with_children(|parent| {
+                    parent.spawn((
+                        TextBundle::from_section(
+                            "",
+                            TextStyle { font_size: 22.0, color: Color::WHITE, ..default() },
+                        ),
+                        WalletDialogText,
+                    ));
+                });
+        });
+}
+
+fn despawn_wallet_dialog(mut commands: Commands, dialog_query: Query<Entity, With<WalletDialogRoot>>) {
+    for entity in dialog_query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+fn wallet_input(
+    mut commands: Commands,
+    mut keyboard_events: EventReader<KeyboardInput>,
+    mut dialog: ResMut<WalletDialog>,
+    mut next_state: ResMut<NextState<AppState>>,
+    config: Option<Res<ChainConfig>>,
+    chain: Res<ChainRuntime>,
+) {
+    let Some(config) = config else {
+        return;
+    };
+    for event in keyboard_events.read() {
+        if event.state != ButtonState::Pressed || dialog.busy {
+            continue;
+        }
+        match &event.logical_key {
+            Key::Escape => next_state.set(AppState::MainMenu),
+            Key::Backspace => {
+                dialog.passphrase.pop();
+            }
+            Key::Character(characters) => {
+                for character in characters.chars() {
+                    if dialog.passphrase.len() + character.len_utf8() <= MAX_PASSPHRASE_BYTES {
+                        dialog.passphrase.push(character);
+                    }
+                }
+            }
+            Key::Enter => match dialog.mode {
+                WalletMode::Unlock => {
+                    if !config.keystore.exists() {
+                        dialog.status = format!("No keystore at {}", config.keystore.display());
+                        continue;
+                    }
+                    let passphrase = std::mem::replace(&mut dialog.passphrase, passphrase_buffer());
+                    dialog.busy = true;
+                    dialog.status = "Unlocking...".to_string();
+                    let path = config.keystore.clone();
+                    open_keystore(&mut commands, &chain, move || {
+                        let key = eth_keystore::decrypt_key(&path, passphrase.as_bytes())
+                            .map_err(|_| "Wrong passphrase, or the keystore is damaged".to_string())?;
+                        wallet_from_key(Zeroizing::new(key))
+                    });
+                }
+                WalletMode::Create => {
+                    if config.keystore.exists() {
+                        dialog.status = format!("A keystore already exists at {}", config.keystore.display());
+                        continue;
+                    }
+                    if dialog.passphrase.chars().count() < MIN_PASSPHRASE_LENGTH {
+                        dialog.status = format!("Use at least {} characters", MIN_PASSPHRASE_LENGTH);
+                        continue;
+                    }
+                    let passphrase = std::mem::replace(&mut dialog.passphrase, passphrase_buffer());
+                    let Some(first_entry) = dialog.first_entry.take() else {
+                        dialog.first_entry = Some(passphrase);
+                        dialog.status = "Type it again to confirm".to_string();
+                        continue;
+                    };
+                    if first_entry != passphrase {
+                        dialog.status = "The passphrases did not match; start over".to_string();
+                        continue;
+                    }
+                    dialog.busy = true;
+                    dialog.status = "Creating keystore...".to_string();
+                    let path = config.keystore.clone();
+                    open_keystore(&mut commands, &chain, move || create_keystore(path, passphrase));
+                }
+            },
+            _ => {}
+        }
+    }
+}
+
+fn create_keystore(path: PathBuf, passphrase: Zeroizing<String>) -> Result<LocalWallet, String> {
+    let (Some(dir), Some(name)) = (path.parent(), path.file_name().and_then(|name| name.to_str())) else {
+        return Err(format!("{} is not a file path", path.display()));
+    };
+    std::fs::create_dir_all(dir).map_err(|err| format!("Could not create {}: {}", dir.display(), err))?;
+    let (key, _uuid) = eth_keystore::new(dir, &mut rand::thread_rng(), passphrase.as_bytes(), Some(name))
+        .map_err(|err| format!("Could not write {}: {}", path.display(), err))?;
+    let wallet = wallet_from_key(Zeroizing::new(key))?;
+    info!("Created keystore {} for {:?}", path.display(), wallet.address());
+    Ok(wallet)
+}
+
+fn wallet_from_key(key: Zeroizing<Vec<u8>>) -> Result<LocalWallet, String> {
+    LocalWallet::from_bytes(&key).map_err(|_| "The keystore does not hold a valid key".to_string())
+}
+
+/// Runs the scrypt work off the frame, then hands the wallet to `init_game`.
+fn open_keystore(
+    commands: &mut Commands,
+    chain: &ChainRuntime,
+    open: impl FnOnce() -> Result<LocalWallet, String> + Send + 'static,
+) {
+    chain.spawn(commands, async move {
+        let result = tokio::task::spawn_blocking(open)
+            .await
+            .unwrap_or_else(|err| Err(format!("Keystore task failed: {}", err)));
+        move |world: &mut World| {
+            let mut dialog = world.resource_mut::<WalletDialog>();
+            dialog.busy = false;
+            match result {
+                Ok(wallet) => {
+                    dialog.status = String::new();
+                    world.send_event(WalletUnlocked(wallet));
+                    world.resource_mut::<NextState<AppState>>().set(AppState::MainMenu);
+                }
+                Err(reason) => dialog.status = reason,
+            }
+        }
+    });
+}
+
+fn update_wallet_text(
+    dialog: Res<WalletDialog>,
+    config: Option<Res<ChainConfig>>,
+    mut text_query: Query<&mut Text, With<WalletDialogText>>,
+) {
+    if !dialog.is_changed() {
+        return;
+    }
+    let path = config.map(|config| config.keystore.display().to_string()).unwrap_or_default();
+    let (title, prompt) = match (dialog.mode, &dialog.first_entry) {
+        (WalletMode::Unlock, _) => ("Unlock wallet", "Passphrase"),
+        (WalletMode::Create, None) => ("Create wallet", "New passphrase"),
+        (WalletMode::Create, Some(_)) => ("Create wallet", "Confirm passphrase"),
+    };
+    let display_text = format!(
+        "{}\n{}\n\n{}: {}_\n\n{}\n\nEnter: continue  Esc: play offline",
+        title,
+        path,
+        prompt,
+        "*".repeat(dialog.passphrase.chars().count()),
+        dialog.status,
+    );
+    for mut text in text_query.iter_mut() {
+        text.sections[0].value = display_text.clone();
+    }
+}
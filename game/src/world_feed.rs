@@ -0,0 +1,162 @@
+use std::collections::VecDeque;
+use std::sync::mpsc::{self, Receiver};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use bevy::prelude::*;
+
+use crate::backend::{Backend, SwordFound};
+use crate::runtime::ChainRuntime;
+use crate::SWORD_COLORS;
+
+const POLL_SECS: f32 = 5.0;
+const FEED_LINES: usize = 5;
+const FEED_VISIBLE: Duration = Duration::from_secs(10);
+const COLOR_NAMES: [&str; SWORD_COLORS] = ["red", "green", "blue", "normal"];
+
+/// World-wide sword totals from `getSwordCounts`.
+#[derive(Resource, Default)]
+pub struct WorldTotals(pub Option<[u64; SWORD_COLORS]>);
+
+#[derive(Resource)]
+struct TotalsPoll {
+    polling: bool,
+    timer: Timer,
+}
+
+/// Finds reported by the backend's subscription.
+#[derive(Resource)]
+struct FoundReceiver(Mutex<Receiver<SwordFound>>);
+
+#[derive(Resource, Default)]
+struct FeedLines(VecDeque<(String, Instant)>);
+
+#[derive(Component)]
+struct WorldFeedText;
+
+pub struct WorldFeedPlugin;
+
+impl Plugin for WorldFeedPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<SwordFound>()
+            .init_resource::<WorldTotals>()
+            .init_resource::<FeedLines>()
+            .insert_resource(TotalsPoll {
+                polling: false,
+                timer: Timer::from_seconds(POLL_SECS, TimerMode::Repeating),
+            })
+            .add_systems(Startup, spawn_world_feed)
+            .add_systems(Update, (
+                subscribe.run_if(resource_added::<Backend>),
+                receive_finds,
+                poll_world_totals,
+                update_world_feed,
+            ).chain());
+    }
+}
+
+fn subscribe(mut commands: Commands, backend: Res<Backend>, chain: Res<ChainRuntime>) {
+    let (sender, receiver) = mpsc::channel();
+    let subscription = backend.subscribe(sender);
+    chain.spawn(&mut commands, async move {
+        subscription.await;
+        |_: &mut World| {}
+    });
+    commands.insert_resource(FoundReceiver(Mutex::new(receiver)));
+}
+
+fn receive_finds(receiver: Option<Res<FoundReceiver>>, mut events: EventWriter<SwordFound>) {
+    if let Some(receiver) = receiver {
+        events.send_batch(receiver.0.lock().unwrap().try_iter());
+    }
+}
+
+fn poll_world_totals(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut poll: ResMut<TotalsPoll>,
+    backend: Option<Res<Backend>>,
+    chain: Res<ChainRuntime>,
+) {
+    if !poll.timer.tick(time.delta()).just_finished() || poll.polling {
+        return;
+    }
+    let Some(backend) = backend else {
+        return;
+    };
+    poll.polling = true;
+    let reading = backend.get_sword_counts();
+    chain.spawn(&mut commands, async move {
+        let totals = reading.await;
+        move |world: &mut World| {
+            world.resource_mut::<TotalsPoll>().polling = false;
+            if let Ok(totals) = totals {
+                world.resource_mut::<WorldTotals>().0 = Some(totals);
+            }
+        }
+    });
+}
+
+fn spawn_world_feed(mut commands: Commands) {
+    commands.spawn((
+        TextBundle::from_section("", TextStyle { font_size: 18.0, color: Color::srgb(0.8, 0.9, 1.0), ..default() })
+            .with_style(Style {
+                position_type: PositionType::Absolute,
+                bottom: Val::Px(10.0),
+                right: Val::Px(10.0),
+                ..default()
+            }),
+        WorldFeedText,
+    ));
+}
+
+fn update_world_feed(
+    mut events: EventReader<SwordFound>,
+    mut feed: ResMut<FeedLines>,
+    world_totals: Res<WorldTotals>,
+    backend: Option<Res<Backend>>,
+    mut text_query: Query<&mut Text, With<WorldFeedText>>,
+) {
+    let me = backend.map(|backend| backend.player());
+    let now = Instant::now();
+    for found in events.read() {
+        if found.player.is_some_and(|player| Some(player) == me) {
+            continue;
+        }
+        let who = match (&found.name, found.player) {
+            (Some(name), _) => name.clone(),
+            (None, Some(player)) => format!("{:?}", player)[..10].to_string(),
+            (None, None) => "Someone".to_string(),
+        };
+        let color = COLOR_NAMES.get(found.color as usize).copied().unwrap_or("mystery");
+        let line = match found.amount {
+            1 => format!("{} found a {} sword", who, color),
+            amount => format!("{} found {} {} swords", who, amount, color),
+        };
+        feed.0.push_back((line, now));
+        if feed.0.len() > FEED_LINES {
+            feed.0.pop_front();
+        }
+    }
+    while feed.0.front().is_some_and(|(_, at)| now.duration_since(*at) >= FEED_VISIBLE) {
+        feed.0.pop_front();
+    }
+    if !world_totals.is_changed() && !feed.is_changed() {
+        return;
+    }
+
+    let mut display_text = match world_totals.0 {
+        Some(totals) => format!(
+            "World: {} red  {} green  {} blue  {} normal",
+            totals[0], totals[1], totals[2], totals[3]
+        ),
+        None => String::new(),
+    };
+    for (line, _) in feed.0.iter() {
+        display_text.push('\n');
+        display_text.push_str(line);
+    }
+    for mut text in text_query.iter_mut() {
+        text.sections[0].value = display_text.clone();
+    }
+}
@@ -0,0 +1,22 @@
+//! Game server side of batch settlement: reads cumulative session results as
+//! JSON and writes the settlement, whose root is then posted with
+//! `postRoot(bytes32)`.
+//!
+//! ```bash
+//! cargo run --example settle -- results.json settlement.json
+//! ```
+
+use sword_merkle::{SessionResult, Settlement};
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let args: Vec<String> = std::env::args().collect();
+    let [_, results_path, settlement_path] = args.as_slice() else {
+        return Err("usage: settle <results.json> <settlement.json>".into());
+    };
+
+    let results: Vec<SessionResult> = serde_json::from_str(&std::fs::read_to_string(results_path)?)?;
+    let settlement = Settlement::build(results);
+    std::fs::write(settlement_path, serde_json::to_string_pretty(&settlement)?)?;
+    println!("Root 0x{} covers {} players", hex::encode(settlement.root), settlement.claims.len());
+    Ok(())
+}
@@ -0,0 +1,202 @@
+//! Merkle trees over session results, matching the sword contract's `claim`.
+//!
+//! Each leaf commits to a player's cumulative sword counts as
+//! `keccak256(keccak256(abi.encode(player, uint256[4] colorCounts)))`; the
+//! double hash keeps leaves from being passed off as inner nodes. Inner nodes
+//! hash their children in sorted order, so proofs carry no left/right flags.
+
+use serde::{Deserialize, Serialize};
+use tiny_keccak::{Hasher, Keccak};
+
+/// Red, green, blue and normal, in the contract's color order.
+pub const COLOR_COUNT: usize = 4;
+
+pub type Hash = [u8; 32];
+pub type Address = [u8; 20];
+
+pub fn keccak256(data: &[u8]) -> Hash {
+    let mut hasher = Keccak::v256();
+    let mut output = [0u8; 32];
+    hasher.update(data);
+    hasher.finalize(&mut output);
+    output
+}
+
+/// Swords a player has earned across all settled sessions so far.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SessionResult {
+    #[serde(with = "hex_bytes")]
+    pub player: Address,
+    pub color_counts: [u64; COLOR_COUNT],
+}
+
+impl SessionResult {
+    pub fn leaf(&self) -> Hash {
+        let mut encoded = [0u8; 32 * (1 + COLOR_COUNT)];
+        encoded[12..32].copy_from_slice(&self.player);
+        for (color, count) in self.color_counts.iter().enumerate() {
+            let end = 32 * (color + 2);
+            encoded[end - 8..end].copy_from_slice(&count.to_be_bytes());
+        }
+        keccak256(&keccak256(&encoded))
+    }
+}
+
+pub fn hash_pair(a: &Hash, b: &Hash) -> Hash {
+    let (low, high) = if a <= b { (a, b) } else { (b, a) };
+    let mut preimage = [0u8; 64];
+    preimage[..32].copy_from_slice(low);
+    preimage[32..].copy_from_slice(high);
+    keccak256(&preimage)
+}
+
+/// Folds `proof` into `leaf` and compares against `root`.
+pub fn verify(proof: &[Hash], root: &Hash, leaf: Hash) -> bool {
+    proof.iter().fold(leaf, |node, sibling| hash_pair(&node, sibling)) == *root
+}
+
+#[derive(Clone, Debug)]
+pub struct MerkleTree {
+    /// `layers[0]` holds the leaves and the last layer the root.
+    layers: Vec<Vec<Hash>>,
+}
+
+impl MerkleTree {
+    /// An unpaired node at the end of a layer moves up unchanged.
+    pub fn new(leaves: Vec<Hash>) -> Self {
+        let mut layers = vec![leaves];
+        while layers.last().unwrap().len() > 1 {
+            let next = layers
+                .last()
+                .unwrap()
+                .chunks(2)
+                .map(|pair| match pair {
+                    [a, b] => hash_pair(a, b),
+                    [a] => *a,
+                    _ => unreachable!(),
+                })
+                .collect();
+            layers.push(next);
+        }
+        MerkleTree { layers }
+    }
+
+    /// The zero hash for an empty tree, which no proof can match.
+    pub fn root(&self) -> Hash {
+        self.layers.last().and_then(|layer| layer.first()).copied().unwrap_or_default()
+    }
+
+    pub fn proof(&self, mut index: usize) -> Option<Vec<Hash>> {
+        if index >= self.layers[0].len() {
+            return None;
+        }
+        let mut proof = Vec::new();
+        for layer in &self.layers[..self.layers.len() - 1] {
+            if let Some(sibling) = layer.get(index ^ 1) {
+                proof.push(*sibling);
+            }
+            index /= 2;
+        }
+        Some(proof)
+    }
+}
+
+/// One player's entry in a [`Settlement`].
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Claim {
+    #[serde(flatten)]
+    pub result: SessionResult,
+    #[serde(with = "hex_hashes")]
+    pub proof: Vec<Hash>,
+}
+
+/// What the game server publishes: the root it posts on-chain and every
+/// player's proof against it.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Settlement {
+    #[serde(with = "hex_bytes")]
+    pub root: Hash,
+    pub claims: Vec<Claim>,
+}
+
+impl Settlement {
+    pub fn build(results: Vec<SessionResult>) -> Self {
+        let tree = MerkleTree::new(results.iter().map(SessionResult::leaf).collect());
+        let claims = results
+            .into_iter()
+            .enumerate()
+            .map(|(index, result)| Claim { result, proof: tree.proof(index).unwrap() })
+            .collect();
+        Settlement { root: tree.root(), claims }
+    }
+
+    pub fn claim_for(&self, player: &Address) -> Option<&Claim> {
+        self.claims.iter().find(|claim| claim.result.player == *player)
+    }
+}
+
+/// Hex strings with a `0x` prefix, which is optional when reading.
+mod hex_bytes {
+    use serde::{de::Error, Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer, const N: usize>(bytes: &[u8; N], serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&format!("0x{}", hex::encode(bytes)))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>, const N: usize>(deserializer: D) -> Result<[u8; N], D::Error> {
+        parse(&String::deserialize(deserializer)?).map_err(D::Error::custom)
+    }
+
+    pub fn parse<const N: usize>(value: &str) -> Result<[u8; N], hex::FromHexError> {
+        let mut bytes = [0u8; N];
+        hex::decode_to_slice(value.trim_start_matches("0x"), &mut bytes)?;
+        Ok(bytes)
+    }
+}
+
+mod hex_hashes {
+    use serde::{de::Error, Deserialize, Deserializer, Serializer};
+
+    use super::Hash;
+
+    pub fn serialize<S: Serializer>(hashes: &[Hash], serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(hashes.iter().map(|hash| format!("0x{}", hex::encode(hash))))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<Hash>, D::Error> {
+        Vec::<String>::deserialize(deserializer)?
+            .iter()
+            .map(|hash| super::hex_bytes::parse(hash).map_err(D::Error::custom))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn result(byte: u8, color_counts: [u64; COLOR_COUNT]) -> SessionResult {
+        SessionResult { player: [byte; 20], color_counts }
+    }
+
+    #[test]
+    fn every_proof_verifies_against_the_root() {
+        for size in 1..=9u8 {
+            let settlement = Settlement::build((0..size).map(|i| result(i, [i as u64, 1, 2, 3])).collect());
+            for claim in &settlement.claims {
+                assert!(verify(&claim.proof, &settlement.root, claim.result.leaf()));
+                let inflated = SessionResult { color_counts: [99, 1, 2, 3], ..claim.result.clone() };
+                assert!(!verify(&claim.proof, &settlement.root, inflated.leaf()));
+            }
+        }
+    }
+
+    #[test]
+    fn settlements_round_trip_through_json() {
+        let settlement = Settlement::build(vec![result(1, [1, 0, 0, 0]), result(2, [0, 2, 0, 0])]);
+        let json = serde_json::to_string(&settlement).unwrap();
+        assert_eq!(serde_json::from_str::<Settlement>(&json).unwrap(), settlement);
+        assert!(settlement.claim_for(&[2; 20]).is_some());
+        assert!(settlement.claim_for(&[3; 20]).is_none());
+    }
+}
@@ -0,0 +1,166 @@
+//! Accepts signed ERC-2771 requests over local HTTP and submits them to the
+//! sword contract, paying the gas. The relayer account must be configured as
+//! the contract's trusted forwarder with `setTrustedForwarder(address)`.
+//!
+//! Nonces are kept in a file next to the binary, or at `RELAYER_NONCES_PATH`,
+//! so requests relayed before a restart cannot be replayed. Deadlines are
+//! still capped at `MAX_REQUEST_TTL_SECS`.
+
+mod nonces;
+mod request;
+
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use axum::extract::{Path, State};
+use axum::http::StatusCode;
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use dotenv::dotenv;
+use ethers::middleware::SignerMiddleware;
+use ethers::providers::{Http, Middleware, Provider};
+use ethers::signers::{LocalWallet, Signer};
+use ethers::types::{Address, TransactionRequest, H256, U256};
+use eyre::eyre;
+use serde::Serialize;
+use serde_json::json;
+use tokio::sync::Mutex;
+
+use nonces::NonceStore;
+use request::SignedRequest;
+
+/// Stylus RPC endpoint url.
+const RPC_URL: &str = "RPC_URL";
+
+/// Deployed sword contract address.
+const STYLUS_CONTRACT_ADDRESS: &str = "STYLUS_CONTRACT_ADDRESS";
+
+/// Key of the account that pays for relayed transactions.
+const RELAYER_PRIVATE_KEY: &str = "RELAYER_PRIVATE_KEY";
+
+/// Local address to listen on.
+const RELAYER_LISTEN_ADDR: &str = "RELAYER_LISTEN_ADDR";
+const DEFAULT_LISTEN_ADDR: &str = "127.0.0.1:8550";
+
+/// File the relayed nonces are kept in.
+const RELAYER_NONCES_PATH: &str = "RELAYER_NONCES_PATH";
+const DEFAULT_NONCES_FILE: &str = "relayer-nonces.json";
+
+const MAX_REQUEST_TTL_SECS: u64 = 60 * 60;
+
+type Client = SignerMiddleware<Provider<Http>, LocalWallet>;
+
+struct Relayer {
+    client: Client,
+    contract: Address,
+    chain_id: u64,
+    /// Next expected nonce per player. Held across submission so relayed
+    /// transactions also go out in order.
+    nonces: Mutex<NonceStore>,
+}
+
+#[derive(Serialize)]
+struct NonceResponse {
+    nonce: U256,
+    chain_id: u64,
+    contract: Address,
+}
+
+#[derive(Serialize)]
+struct RelayResponse {
+    tx_hash: H256,
+}
+
+type ApiError = (StatusCode, Json<serde_json::Value>);
+
+fn reject(status: StatusCode, message: impl ToString) -> ApiError {
+    (status, Json(json!({ "error": message.to_string() })))
+}
+
+#[tokio::main]
+async fn main() -> eyre::Result<()> {
+    dotenv().ok();
+    let rpc_url = std::env::var(RPC_URL).map_err(|_| eyre!("No {} env var set", RPC_URL))?;
+    let contract_address = std::env::var(STYLUS_CONTRACT_ADDRESS)
+        .map_err(|_| eyre!("No {} env var set", STYLUS_CONTRACT_ADDRESS))?;
+    let private_key = std::env::var(RELAYER_PRIVATE_KEY)
+        .map_err(|_| eyre!("No {} env var set", RELAYER_PRIVATE_KEY))?;
+    let listen_addr: SocketAddr = std::env::var(RELAYER_LISTEN_ADDR)
+        .unwrap_or_else(|_| DEFAULT_LISTEN_ADDR.to_string())
+        .parse()?;
+    let nonces_path = match std::env::var(RELAYER_NONCES_PATH) {
+        Ok(path) => PathBuf::from(path),
+        Err(_) => std::env::current_exe()?.with_file_name(DEFAULT_NONCES_FILE),
+    };
+    let nonces = NonceStore::open(nonces_path.clone())
+        .map_err(|err| eyre!("Could not read nonces from {}: {}", nonces_path.display(), err))?;
+
+    let provider = Provider::<Http>::try_from(rpc_url)?;
+    let chain_id = provider.get_chainid().await?.as_u64();
+    let wallet = private_key.parse::<LocalWallet>()?.with_chain_id(chain_id);
+    println!("Relaying from {:?}", wallet.address());
+
+    let relayer = Arc::new(Relayer {
+        client: SignerMiddleware::new(provider, wallet),
+        contract: contract_address.parse()?,
+        chain_id,
+        nonces: Mutex::new(nonces),
+    });
+    let app = Router::new()
+        .route("/nonce/:player", get(nonce))
+        .route("/relay", post(relay))
+        .with_state(relayer);
+
+    let listener = tokio::net::TcpListener::bind(listen_addr).await?;
+    println!("Listening on http://{}", listen_addr);
+    axum::serve(listener, app).await?;
+    Ok(())
+}
+
+async fn nonce(State(relayer): State<Arc<Relayer>>, Path(player): Path<Address>) -> Json<NonceResponse> {
+    let nonce = relayer.nonces.lock().await.get(player);
+    Json(NonceResponse { nonce, chain_id: relayer.chain_id, contract: relayer.contract })
+}
+
+async fn relay(
+    State(relayer): State<Arc<Relayer>>,
+    Json(signed): Json<SignedRequest>,
+) -> Result<Json<RelayResponse>, ApiError> {
+    let request = &signed.request;
+    if request.to != relayer.contract || request.chain_id != relayer.chain_id {
+        return Err(reject(StatusCode::BAD_REQUEST, "request is for another contract or chain"));
+    }
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
+    if request.deadline <= U256::from(now) {
+        return Err(reject(StatusCode::BAD_REQUEST, "request expired"));
+    }
+    if request.deadline > U256::from(now + MAX_REQUEST_TTL_SECS) {
+        return Err(reject(StatusCode::BAD_REQUEST, "deadline too far in the future"));
+    }
+    signed.verify().map_err(|err| reject(StatusCode::UNAUTHORIZED, err))?;
+
+    let mut nonces = relayer.nonces.lock().await;
+    let expected = nonces.get(request.from);
+    if request.nonce != expected {
+        return Err(reject(StatusCode::CONFLICT, format!("expected nonce {}", expected)));
+    }
+    // Spend the nonce on disk before sending, so a crash after the send
+    // cannot leave it open for a replay.
+    nonces
+        .set(request.from, expected + 1)
+        .map_err(|err| reject(StatusCode::INTERNAL_SERVER_ERROR, err))?;
+
+    let tx = TransactionRequest::new().to(relayer.contract).data(signed.forwarded_calldata());
+    let pending = match relayer.client.send_transaction(tx, None).await {
+        Ok(pending) => pending,
+        Err(err) => {
+            if let Err(save_err) = nonces.set(request.from, expected) {
+                eprintln!("Could not give back nonce {} of {:?}: {}", expected, request.from, save_err);
+            }
+            return Err(reject(StatusCode::BAD_GATEWAY, err));
+        }
+    };
+    Ok(Json(RelayResponse { tx_hash: pending.tx_hash() }))
+}
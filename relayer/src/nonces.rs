@@ -0,0 +1,89 @@
+use std::collections::HashMap;
+use std::fs;
+use std::io::ErrorKind;
+use std::path::PathBuf;
+
+use ethers::types::{Address, U256};
+
+/// Next expected nonce per player, written to disk on every change so a
+/// restarted relayer still refuses requests it has already relayed.
+pub struct NonceStore {
+    path: PathBuf,
+    nonces: HashMap<Address, U256>,
+}
+
+impl NonceStore {
+    /// Loads the store at `path`. A missing file is an empty store.
+    pub fn open(path: PathBuf) -> eyre::Result<Self> {
+        let nonces = match fs::read(&path) {
+            Ok(bytes) => serde_json::from_slice(&bytes)?,
+            Err(err) if err.kind() == ErrorKind::NotFound => HashMap::new(),
+            Err(err) => return Err(err.into()),
+        };
+        Ok(Self { path, nonces })
+    }
+
+    pub fn get(&self, player: Address) -> U256 {
+        self.nonces.get(&player).copied().unwrap_or_default()
+    }
+
+    /// Sets `player`'s next nonce and saves the store. The file is replaced
+    /// through a rename so a crash mid-write leaves the old nonces intact.
+    pub fn set(&mut self, player: Address, nonce: U256) -> eyre::Result<()> {
+        let previous = self.nonces.insert(player, nonce);
+        let saved = self.save();
+        if saved.is_err() {
+            match previous {
+                Some(previous) => self.nonces.insert(player, previous),
+                None => self.nonces.remove(&player),
+            };
+        }
+        saved
+    }
+
+    fn save(&self) -> eyre::Result<()> {
+        let tmp = self.path.with_extension("tmp");
+        fs::write(&tmp, serde_json::to_vec(&self.nonces)?)?;
+        fs::rename(&tmp, &self.path)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_path(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("relayer-nonces-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir.join("nonces.json")
+    }
+
+    #[test]
+    fn nonces_survive_a_restart() {
+        let path = temp_path("restart");
+        let player = Address::repeat_byte(7);
+
+        let mut store = NonceStore::open(path.clone()).unwrap();
+        assert_eq!(store.get(player), U256::zero());
+        store.set(player, U256::from(3)).unwrap();
+
+        let reopened = NonceStore::open(path).unwrap();
+        assert_eq!(reopened.get(player), U256::from(3));
+        assert_eq!(reopened.get(Address::repeat_byte(8)), U256::zero());
+    }
+
+    #[test]
+    fn a_failed_save_leaves_the_nonce_unchanged() {
+        let path = temp_path("failed-save");
+        let player = Address::repeat_byte(7);
+        let mut store = NonceStore::open(path.clone()).unwrap();
+        store.set(player, U256::from(1)).unwrap();
+
+        // A directory in the way of the temporary file makes the write fail.
+        fs::create_dir_all(path.with_extension("tmp")).unwrap();
+        assert!(store.set(player, U256::from(2)).is_err());
+        assert_eq!(store.get(player), U256::from(1));
+    }
+}
@@ -0,0 +1,104 @@
+use ethers::abi::{encode, Token};
+use ethers::types::transaction::eip712::{EIP712Domain, Eip712};
+use ethers::types::{Address, Bytes, Signature, U256};
+use ethers::utils::keccak256;
+use serde::{Deserialize, Serialize};
+
+/// Must match the game's domain; the verifying contract is the sword contract
+/// the request targets, since the relayer itself is an account, not a contract.
+const DOMAIN_NAME: &str = "BevyStylusRelayer";
+const DOMAIN_VERSION: &str = "1";
+const FORWARD_REQUEST_TYPE: &str =
+    "ForwardRequest(address from,address to,bytes data,uint256 nonce,uint256 deadline)";
+
+/// A call the player signed for the relayer to submit on their behalf.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ForwardRequest {
+    pub from: Address,
+    pub to: Address,
+    pub data: Bytes,
+    pub nonce: U256,
+    pub deadline: U256,
+    pub chain_id: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SignedRequest {
+    pub request: ForwardRequest,
+    pub signature: Bytes,
+}
+
+impl Eip712 for ForwardRequest {
+    type Error = std::convert::Infallible;
+
+    fn domain(&self) -> Result<EIP712Domain, Self::Error> {
+        Ok(EIP712Domain {
+            name: Some(DOMAIN_NAME.to_string()),
+            version: Some(DOMAIN_VERSION.to_string()),
+            chain_id: Some(self.chain_id.into()),
+            verifying_contract: Some(self.to),
+            salt: None,
+        })
+    }
+
+    fn type_hash() -> Result<[u8; 32], Self::Error> {
+        Ok(keccak256(FORWARD_REQUEST_TYPE))
+    }
+
+    fn struct_hash(&self) -> Result<[u8; 32], Self::Error> {
+        Ok(keccak256(encode(&[
+            Token::FixedBytes(Self::type_hash()?.to_vec()),
+            Token::Address(self.from),
+            Token::Address(self.to),
+            Token::FixedBytes(keccak256(&self.data).to_vec()),
+            Token::Uint(self.nonce),
+            Token::Uint(self.deadline),
+        ])))
+    }
+}
+
+impl SignedRequest {
+    /// Checks the signature belongs to `request.from`.
+    pub fn verify(&self) -> eyre::Result<()> {
+        let signature = Signature::try_from(self.signature.as_ref())?;
+        let digest = self.request.encode_eip712()?;
+        signature.verify(digest, self.request.from)?;
+        Ok(())
+    }
+
+    /// ERC-2771 calldata: the signed call followed by the player's address.
+    pub fn forwarded_calldata(&self) -> Bytes {
+        let mut calldata = self.request.data.to_vec();
+        calldata.extend_from_slice(self.request.from.as_bytes());
+        calldata.into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethers::signers::{LocalWallet, Signer};
+
+    #[tokio::test]
+    async fn only_the_player_can_sign_for_themselves() {
+        let player: LocalWallet = "0xb6b15c8cb491557369f3c7d2c287b053eb229daa9c22138887752191c9520659".parse().unwrap();
+        let request = ForwardRequest {
+            from: player.address(),
+            to: Address::repeat_byte(0x5a),
+            data: vec![0xaa, 0xbb, 0xcc, 0xdd].into(),
+            nonce: U256::zero(),
+            deadline: U256::from(1_700_000_000u64),
+            chain_id: 412346,
+        };
+        let signature = player.sign_typed_data(&request).await.unwrap();
+        let signed = SignedRequest { request: request.clone(), signature: signature.to_vec().into() };
+        signed.verify().unwrap();
+        assert_eq!(&signed.forwarded_calldata()[4..], player.address().as_bytes());
+
+        let forged = SignedRequest {
+            request: ForwardRequest { from: Address::repeat_byte(0x01), ..request },
+            signature: signed.signature.clone(),
+        };
+        assert!(forged.verify().is_err());
+    }
+}